@@ -1,12 +1,17 @@
 use super::{
-    error::{ValidationError, ValidationResult},
-    json::{from_json, Array, Json, JsonType},
-    OkSchema, Validator,
+    context::Context,
+    de::Streaming,
+    error::{PathSegment, ValidationError, ValidationResult},
+    json::{Array, Json, JsonType},
+    spec::{from_spec, SchemaSpec, SpecError},
+    OkSchema, TestOutcome, Validator,
 };
+use serde_json::json;
+use std::collections::HashSet;
 
 pub struct ArraySchema {
     validator: Validator<Array>,
-    element_schema: Option<Box<OkSchema>>,
+    element_schema: Option<Box<dyn OkSchema>>,
 }
 
 impl ArraySchema {
@@ -17,47 +22,146 @@ impl ArraySchema {
         }
     }
 
+    pub fn test<M, F, O>(mut self, name: &'static str, message: M, test: F) -> Self
+    where
+        M: Into<String>,
+        F: Fn(&Array) -> std::result::Result<O, ValidationError> + 'static,
+        O: Into<TestOutcome>,
+    {
+        self.validator
+            .add_test(name, message, Json::Null, test)
+            .custom();
+        self
+    }
+
     pub fn length(mut self, (min, max): (usize, usize)) -> Self {
-        self.validator.add_test(
-            "length",
-            format!("<label> must have length between {} and {}.", min, max),
-            move |array| Ok(array.len() >= min && array.len() <= max),
-        );
+        self.validator
+            .add_test(
+                "length",
+                format!("<label> must have length between {} and {}.", min, max),
+                json!({ "min": min, "max": max }),
+                move |array| Ok(array.len() >= min && array.len() <= max),
+            )
+            .measure(|array| json!(array.len()));
         self
     }
 
     pub fn min_length(mut self, min: usize) -> Self {
-        self.validator.add_test(
-            "min_length",
-            format!("<label> must contain at least {} elements.", min),
-            move |array| Ok(array.len() >= min),
-        );
+        self.validator
+            .add_test(
+                "min_length",
+                format!("<label> must contain at least {} elements.", min),
+                json!({ "min": min }),
+                move |array| Ok(array.len() >= min),
+            )
+            .measure(|array| json!(array.len()));
         self
     }
 
     pub fn max_length(mut self, max: usize) -> Self {
+        self.validator
+            .add_test(
+                "max_length",
+                format!("<label> may contain at most {} elements.", max),
+                json!({ "max": max }),
+                move |array| Ok(array.len() <= max),
+            )
+            .measure(|array| json!(array.len()));
+        self
+    }
+
+    /// Runs on the raw elements, before `of()` validates each one. Numbers
+    /// compare by their JSON representation, so `1` and `1.0` are distinct.
+    pub fn unique(mut self) -> Self {
         self.validator.add_test(
-            "max_length",
-            format!("<label> may contain at most {} elements.", max),
-            move |array| Ok(array.len() <= max),
+            "unique",
+            "<label> must not contain duplicate elements.",
+            Json::Null,
+            |array| {
+                Ok(match first_duplicate(array.iter()) {
+                    Some(duplicate) => TestOutcome::FailMeasured(duplicate.clone()),
+                    None => TestOutcome::Pass,
+                })
+            },
         );
         self
     }
 
+    /// Ignores non-object elements and elements where `key` is missing or null.
+    pub fn unique_by(self, key: &str) -> Self {
+        let params = json!({ "key": key });
+        let message = format!("<label> must not contain duplicate values of `{}`.", key);
+        let key = key.to_string();
+        self.unique_by_keys(message, params, move |element| {
+            element.get(&key).cloned().unwrap_or(Json::Null)
+        })
+    }
+
+    /// Ignores elements whose extracted key is null.
+    pub fn unique_by_fn<F>(self, extract: F) -> Self
+    where
+        F: Fn(&Json) -> Json + 'static,
+    {
+        let message = "<label> must not contain duplicate keys.".to_string();
+        let mut schema = self.unique_by_keys(message, Json::Null, extract);
+        schema.validator.tests.last_mut().unwrap().custom();
+        schema
+    }
+
+    fn unique_by_keys<F>(mut self, message: String, params: Json, extract: F) -> Self
+    where
+        F: Fn(&Json) -> Json + 'static,
+    {
+        self.validator
+            .add_test("unique_by", message, params, move |array| {
+                let keys = array
+                    .iter()
+                    .map(&extract)
+                    .filter(|key| !key.is_null())
+                    .collect::<Vec<_>>();
+                Ok(first_duplicate(keys.iter()).is_none())
+            });
+        self
+    }
+
     pub fn of(mut self, schema: impl OkSchema + 'static) -> Self {
         self.element_schema = Some(Box::new(schema));
         self
     }
+
+    pub fn from_spec(spec: &SchemaSpec) -> Result<Self, SpecError> {
+        let mut schema = ArraySchema::new();
+        schema.validator.load_spec(spec);
+        if let Some(elements) = &spec.elements {
+            schema.element_schema = Some(from_spec(elements)?);
+        }
+        if let Some(rule) = spec.transforms.first() {
+            return Err(SpecError::UnknownRule(rule.name.clone()));
+        }
+        let mut schema =
+            spec.tests
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "length" => Ok(schema.length((rule.param("min")?, rule.param("max")?))),
+                    "min_length" => Ok(schema.min_length(rule.param("min")?)),
+                    "max_length" => Ok(schema.max_length(rule.param("max")?)),
+                    "unique" => Ok(schema.unique()),
+                    "unique_by" => Ok(schema.unique_by(&rule.param::<String>("key")?)),
+                    name => Err(SpecError::UnknownRule(name.into())),
+                })?;
+        schema.validator.load_messages(&spec.tests);
+        Ok(schema)
+    }
 }
 
 impl OkSchema for ArraySchema {
     fn label(mut self, label: &'static str) -> Self {
-        self.validator.label = Some(label);
+        self.validator.label = Some(label.into());
         self
     }
 
     fn desc(mut self, description: &'static str) -> Self {
-        self.validator.description = Some(description);
+        self.validator.description = Some(description.into());
         self
     }
 
@@ -71,43 +175,98 @@ impl OkSchema for ArraySchema {
         self
     }
 
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
     fn validate_at(
         &self,
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>> {
         let mut errors = vec![];
-        let validated = self.validator.exec(path, value, &mut errors);
-        let elements = match validated {
-            Ok(None) => return Ok(None),
-            Ok(Some(json)) => {
-                if self.element_schema.is_none() {
-                    return Ok(Some(json));
-                }
-                from_json::<Array>(json).unwrap()
+        let validated = self.validator.exec(path, value, &mut errors, context);
+        let (elements, element_schema) = match (validated, &self.element_schema) {
+            (Ok(Some(Json::Array(elements))), Some(element_schema)) => (elements, element_schema),
+            (Ok(value), _) => return Ok(value),
+            (Err(_), _) => {
+                all_errors.append(&mut errors);
+                return Err(());
             }
-            Err(_) => return Err(all_errors.append(&mut errors)),
         };
         let mut array = vec![];
-        let element_schema = self.element_schema.as_ref().unwrap();
-        elements
-            .into_iter()
-            .enumerate()
-            .for_each(|(index, element)| {
-                let path = format!("{}[{}]", path, index);
-                if let Ok(validated) =
-                    element_schema.validate_at(path.as_str(), Some(element), &mut errors)
-                {
-                    if errors.is_empty() {
-                        array.push(validated.unwrap());
-                    }
+        for (index, element) in elements.into_iter().enumerate() {
+            let path = format!("{}[{}]", path, index);
+            if context.exhausted(&path, &mut errors) {
+                break;
+            }
+            context.push(PathSegment::Index(index));
+            if let Ok(validated) =
+                element_schema.validate_at(path.as_str(), Some(element), &mut errors, context)
+            {
+                if errors.is_empty() {
+                    array.push(validated.unwrap_or(Json::Null));
                 }
-            });
+            }
+            context.pop();
+        }
         if errors.is_empty() {
             return Ok(Some(array.into()));
         }
-        Err(all_errors.append(&mut errors))
+        all_errors.extend(errors.into_iter().map(|error| self.validator.redact(error)));
+        Err(())
+    }
+
+    fn streaming(&self) -> Streaming<'_> {
+        let validator = &self.validator;
+        match &self.element_schema {
+            Some(schema) if validator.tests.is_empty() && validator.transforms.is_empty() => {
+                Streaming::Elements {
+                    schema: schema.as_ref(),
+                    sensitive: validator.is_sensitive,
+                }
+            }
+            _ => Streaming::Buffered,
+        }
+    }
+
+    fn to_spec(&self) -> Result<SchemaSpec, SpecError> {
+        let mut spec = self.validator.to_spec()?;
+        if let Some(element_schema) = &self.element_schema {
+            spec.elements = Some(Box::new(element_schema.to_spec()?));
+        }
+        Ok(spec)
+    }
+}
+
+fn first_duplicate<'a>(mut values: impl Iterator<Item = &'a Json>) -> Option<&'a Json> {
+    let mut seen = HashSet::new();
+    values.find(|value| !seen.insert(canonical(value)))
+}
+
+fn canonical(value: &Json) -> String {
+    match value {
+        Json::Array(elements) => {
+            let elements = elements.iter().map(canonical).collect::<Vec<_>>();
+            format!("[{}]", elements.join(","))
+        }
+        Json::Object(object) => {
+            let mut entries = object
+                .iter()
+                .map(|(key, value)| format!("{}:{}", Json::from(key.as_str()), canonical(value)))
+                .collect::<Vec<_>>();
+            entries.sort();
+            format!("{{{}}}", entries.join(","))
+        }
+        scalar => scalar.to_string(),
     }
 }
 
@@ -120,11 +279,12 @@ mod tests {
     use super::super::{
         array, boolean,
         error::{json_error, test_error, type_error},
-        integer,
-        json::JsonType,
-        object, string, OkSchema,
+        from_spec, integer,
+        json::{Json, JsonType},
+        object, string, ErrorCode, OkSchema, Options, SpecError,
     };
     use serde_json::json;
+    use std::{thread, time::Duration};
 
     #[test]
     fn it_validates_arrays() {
@@ -132,23 +292,48 @@ mod tests {
         assert_eq!(schema.validate(json!([])), Ok(json!([])));
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Array,
+                Some(&json!(null))
+            )]))
         );
         assert_eq!(
             schema.validate(json!({})),
-            Err(json_error(vec![type_error("", "", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Array,
+                Some(&json!({}))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(1)),
-            Err(json_error(vec![type_error("", "", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Array,
+                Some(&json!(1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(true)),
-            Err(json_error(vec![type_error("", "", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Array,
+                Some(&json!(true))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Array,
+                Some(&json!("foo"))
+            )]))
         );
     }
 
@@ -165,7 +350,9 @@ mod tests {
                 "length",
                 "",
                 "My Array must have length between 1 and 3."
-            )]))
+            )
+            .with_label("My Array")
+            .with_measure(json!({ "min": 1, "max": 3 }), json!(0))]))
         );
         assert_eq!(
             schema.validate(json!(["foo", "bar", "baz", "qux"])),
@@ -173,7 +360,9 @@ mod tests {
                 "length",
                 "",
                 "My Array must have length between 1 and 3."
-            )]))
+            )
+            .with_label("My Array")
+            .with_measure(json!({ "min": 1, "max": 3 }), json!(4))]))
         );
     }
 
@@ -190,7 +379,9 @@ mod tests {
                 "min_length",
                 "",
                 "My Array must contain at least 4 elements."
-            )]))
+            )
+            .with_label("My Array")
+            .with_measure(json!({ "min": 4 }), json!(1))]))
         );
     }
 
@@ -207,7 +398,9 @@ mod tests {
                 "max_length",
                 "",
                 "My Array may contain at most 3 elements."
-            )]))
+            )
+            .with_label("My Array")
+            .with_measure(json!({ "max": 3 }), json!(4))]))
         );
     }
 
@@ -221,9 +414,9 @@ mod tests {
         assert_eq!(
             schema.validate(json!([1, 2, 3])),
             Err(json_error(vec![
-                type_error("[0]", "[0]", JsonType::Boolean),
-                type_error("[1]", "[1]", JsonType::Boolean),
-                type_error("[2]", "[2]", JsonType::Boolean)
+                type_error("[0]", "[0]", JsonType::Boolean, Some(&json!(1))),
+                type_error("[1]", "[1]", JsonType::Boolean, Some(&json!(2))),
+                type_error("[2]", "[2]", JsonType::Boolean, Some(&json!(3)))
             ]))
         );
     }
@@ -235,9 +428,9 @@ mod tests {
         assert_eq!(
             schema.validate(json!(["foo", "bar", "baz"])),
             Err(json_error(vec![
-                type_error("[0]", "[0]", JsonType::Integer),
-                type_error("[1]", "[1]", JsonType::Integer),
-                type_error("[2]", "[2]", JsonType::Integer)
+                type_error("[0]", "[0]", JsonType::Integer, Some(&json!("foo"))),
+                type_error("[1]", "[1]", JsonType::Integer, Some(&json!("bar"))),
+                type_error("[2]", "[2]", JsonType::Integer, Some(&json!("baz")))
             ]))
         );
     }
@@ -252,9 +445,9 @@ mod tests {
         assert_eq!(
             schema.validate(json!([null, null, null])),
             Err(json_error(vec![
-                type_error("[0]", "[0]", JsonType::String),
-                type_error("[1]", "[1]", JsonType::String),
-                type_error("[2]", "[2]", JsonType::String)
+                type_error("[0]", "[0]", JsonType::String, Some(&json!(null))),
+                type_error("[1]", "[1]", JsonType::String, Some(&json!(null))),
+                type_error("[2]", "[2]", JsonType::String, Some(&json!(null)))
             ]))
         );
     }
@@ -269,9 +462,9 @@ mod tests {
         assert_eq!(
             schema.validate(json!(["foo", "bar", "baz"])),
             Err(json_error(vec![
-                type_error("[0]", "[0]", JsonType::Object),
-                type_error("[1]", "[1]", JsonType::Object),
-                type_error("[2]", "[2]", JsonType::Object)
+                type_error("[0]", "[0]", JsonType::Object, Some(&json!("foo"))),
+                type_error("[1]", "[1]", JsonType::Object, Some(&json!("bar"))),
+                type_error("[2]", "[2]", JsonType::Object, Some(&json!("baz")))
             ]))
         );
     }
@@ -286,10 +479,187 @@ mod tests {
         assert_eq!(
             schema.validate(json!([1, 2, 3])),
             Err(json_error(vec![
-                type_error("[0]", "[0]", JsonType::Array),
-                type_error("[1]", "[1]", JsonType::Array),
-                type_error("[2]", "[2]", JsonType::Array)
+                type_error("[0]", "[0]", JsonType::Array, Some(&json!(1))),
+                type_error("[1]", "[1]", JsonType::Array, Some(&json!(2))),
+                type_error("[2]", "[2]", JsonType::Array, Some(&json!(3)))
             ]))
         );
     }
+
+    #[test]
+    fn it_aborts_when_the_time_budget_is_exhausted() {
+        let mut slow = array();
+        slow.validator
+            .add_test("slow", "<label> is slow.", Json::Null, |_| {
+                thread::sleep(Duration::from_millis(2));
+                Ok(true)
+            });
+        let schema = object().key("rows", array().of(slow));
+        let options = Options {
+            time_budget: Some(Duration::from_millis(10)),
+            ..Options::default()
+        };
+        let error = schema
+            .validate_with(json!({ "rows": vec![json!([]); 100] }), options)
+            .unwrap_err();
+        let errors = error.flatten().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), &ErrorCode::Timeout);
+        assert!(errors[0].path().starts_with("rows["));
+        let processed = errors[0].params()["processed"].as_u64().unwrap();
+        assert!(processed > 1 && processed < 100);
+    }
+
+    #[test]
+    fn it_ignores_the_time_budget_for_fast_payloads() {
+        let schema = array().of(object().integer("id", |id| id.min(1)));
+        let options = Options {
+            time_budget: Some(Duration::from_secs(10)),
+            ..Options::default()
+        };
+        assert_eq!(
+            schema
+                .validate_with(json!([{ "id": 1 }, { "id": 2 }]), options)
+                .map(|validated| validated.value),
+            Ok(json!([{ "id": 1 }, { "id": 2 }]))
+        );
+    }
+
+    #[test]
+    fn it_runs_custom_tests_alongside_built_in_tests() {
+        let schema = array().label("Tags").min_length(2).test(
+            "unique",
+            "<label> must not repeat.",
+            |tags| {
+                Ok(tags
+                    .iter()
+                    .enumerate()
+                    .all(|(i, tag)| !tags[..i].contains(tag)))
+            },
+        );
+        assert_eq!(schema.validate(json!(["a", "b"])), Ok(json!(["a", "b"])));
+        assert_eq!(
+            schema.validate(json!(["a", "a"])),
+            Err(json_error(vec![test_error(
+                "unique",
+                "",
+                "Tags must not repeat."
+            )
+            .with_label("Tags")]))
+        );
+        assert_eq!(
+            schema.validate(json!([[]])),
+            Err(json_error(vec![test_error(
+                "min_length",
+                "",
+                "Tags must contain at least 2 elements."
+            )
+            .with_label("Tags")
+            .with_measure(json!({ "min": 2 }), json!(1))]))
+        );
+    }
+
+    #[test]
+    fn it_requires_unique_elements() {
+        let schema = array().label("Tags").unique();
+        assert_eq!(
+            schema.validate(json!(["a", "b", "c"])),
+            Ok(json!(["a", "b", "c"]))
+        );
+        assert_eq!(
+            schema.validate(json!(["a", "b", "c", "b", "a"])),
+            Err(json_error(vec![test_error(
+                "unique",
+                "",
+                "Tags must not contain duplicate elements."
+            )
+            .with_label("Tags")
+            .with_measure(json!(null), json!("b"))]))
+        );
+        let duplicate = json!({ "id": 1, "tags": ["x"] });
+        assert_eq!(
+            schema.validate(json!([{ "tags": ["x"], "id": 1 }, duplicate])),
+            Err(json_error(vec![test_error(
+                "unique",
+                "",
+                "Tags must not contain duplicate elements."
+            )
+            .with_label("Tags")
+            .with_measure(json!(null), duplicate)]))
+        );
+        assert_eq!(schema.validate(json!([1, 1.0])), Ok(json!([1, 1.0])));
+    }
+
+    #[test]
+    fn it_checks_uniqueness_before_validating_elements() {
+        let schema = array().label("Ids").unique().of(integer());
+        assert_eq!(schema.validate(json!([1, "1"])), Ok(json!([1, 1])));
+        assert!(schema.validate(json!([1, 1])).is_err());
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!(["2", "2"])).is_err());
+    }
+
+    #[test]
+    fn it_requires_unique_values_of_a_key() {
+        let schema = array().label("Line items").unique_by("sku");
+        let items = json!([
+            { "sku": "A-1", "qty": 1 },
+            { "sku": "B-2", "qty": 1 },
+            { "qty": 3 },
+            { "qty": 4 },
+            { "sku": null },
+            { "sku": null },
+            "A-1",
+            7
+        ]);
+        assert_eq!(schema.validate(items.clone()), Ok(items));
+        assert_eq!(
+            schema.validate(json!([{ "sku": "A-1", "qty": 1 }, { "sku": "A-1", "qty": 2 }])),
+            Err(json_error(vec![test_error(
+                "unique_by",
+                "",
+                "Line items must not contain duplicate values of `sku`."
+            )
+            .with_label("Line items")
+            .with_params(json!({ "key": "sku" }))]))
+        );
+        assert_eq!(
+            schema.validate(json!([{ "sku": 1 }, { "sku": "1" }, { "sku": 1.0 }])),
+            Ok(json!([{ "sku": 1 }, { "sku": "1" }, { "sku": 1.0 }]))
+        );
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema
+            .validate(json!([{ "sku": "A" }, { "sku": "A" }]))
+            .is_err());
+    }
+
+    #[test]
+    fn it_requires_unique_values_of_an_extracted_key() {
+        let schema = array().label("Variants").unique_by_fn(|element| {
+            element
+                .pointer("/product/sku")
+                .cloned()
+                .unwrap_or(Json::Null)
+        });
+        assert_eq!(
+            schema.validate(json!([{ "product": { "sku": "A" } }, { "product": {} }])),
+            Ok(json!([{ "product": { "sku": "A" } }, { "product": {} }]))
+        );
+        assert_eq!(
+            schema.validate(json!([
+                { "product": { "sku": "A" } },
+                { "product": { "sku": "A" }, "size": "XL" }
+            ])),
+            Err(json_error(vec![test_error(
+                "unique_by",
+                "",
+                "Variants must not contain duplicate keys."
+            )
+            .with_label("Variants")]))
+        );
+        assert_eq!(
+            schema.to_spec(),
+            Err(SpecError::Unserializable("custom test `unique_by`".into()))
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use super::{
+    context::Context,
     error::{ValidationError, ValidationResult},
-    json::{Json, JsonType},
-    OkSchema, Validator,
+    json::{Coercions, Json, JsonType},
+    spec::{SchemaSpec, SpecError},
+    OkSchema, TestOutcome, Validator,
 };
 
 pub struct BooleanSchema {
@@ -14,16 +16,104 @@ impl BooleanSchema {
             validator: Validator::new(JsonType::Boolean),
         }
     }
+
+    pub fn test<M, F, O>(mut self, name: &'static str, message: M, test: F) -> Self
+    where
+        M: Into<String>,
+        F: Fn(&bool) -> std::result::Result<O, ValidationError> + 'static,
+        O: Into<TestOutcome>,
+    {
+        self.validator
+            .add_test(name, message, Json::Null, test)
+            .custom();
+        self
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn is_true(mut self) -> Self {
+        self.validator
+            .add_test("is_true", "<label> must be true.", Json::Null, |b| Ok(*b));
+        self
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn is_false(mut self) -> Self {
+        self.validator
+            .add_test("is_false", "<label> must be false.", Json::Null, |b| {
+                Ok(!*b)
+            });
+        self
+    }
+
+    pub fn invert(mut self) -> Self {
+        self.validator.add_transform("invert", |b: bool| !b);
+        self
+    }
+
+    pub fn default_value(mut self, value: bool) -> Self {
+        self.validator.default = Some(Json::Bool(value));
+        self
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.validator.coercions = self
+            .validator
+            .coercions
+            .allow_string_to_boolean(false)
+            .allow_number_to_boolean(false);
+        self
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_numbers(mut self) -> Self {
+        self.validator.coercions = self.validator.coercions.allow_number_to_boolean(true);
+        self
+    }
+
+    pub fn truthy_strings(mut self) -> Self {
+        self.validator.coercions = self.validator.coercions.allow_truthy_strings(true);
+        self
+    }
+
+    pub fn coercions<F>(mut self, configure: F) -> Self
+    where
+        F: FnOnce(Coercions) -> Coercions,
+    {
+        self.validator.coercions = configure(self.validator.coercions);
+        self
+    }
+
+    pub fn from_spec(spec: &SchemaSpec) -> Result<Self, SpecError> {
+        let mut schema = BooleanSchema::new();
+        schema.validator.load_spec(spec);
+        let schema =
+            spec.transforms
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "invert" => Ok(schema.invert()),
+                    name => Err(SpecError::UnknownRule(name.into())),
+                })?;
+        let mut schema =
+            spec.tests
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "is_true" => Ok(schema.is_true()),
+                    "is_false" => Ok(schema.is_false()),
+                    name => Err(SpecError::UnknownRule(name.into())),
+                })?;
+        schema.validator.load_messages(&spec.tests);
+        Ok(schema)
+    }
 }
 
 impl OkSchema for BooleanSchema {
     fn label(mut self, label: &'static str) -> Self {
-        self.validator.label = Some(label);
+        self.validator.label = Some(label.into());
         self
     }
 
     fn desc(mut self, description: &'static str) -> Self {
-        self.validator.description = Some(description);
+        self.validator.description = Some(description.into());
         self
     }
 
@@ -37,13 +127,28 @@ impl OkSchema for BooleanSchema {
         self
     }
 
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
     fn validate_at(
         &self,
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>> {
-        self.validator.exec(path, value, all_errors)
+        self.validator.exec(path, value, all_errors, context)
+    }
+
+    fn to_spec(&self) -> Result<SchemaSpec, SpecError> {
+        self.validator.to_spec()
     }
 }
 
@@ -55,9 +160,10 @@ pub fn boolean() -> BooleanSchema {
 mod tests {
     use super::super::{
         boolean,
-        error::{json_error, type_error},
+        error::{json_error, test_error, type_error},
+        from_spec,
         json::JsonType,
-        OkSchema,
+        object, OkSchema,
     };
     use serde_json::json;
 
@@ -70,23 +176,251 @@ mod tests {
         assert_eq!(schema.validate(json!("false")), Ok(json!(false)));
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Boolean)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!(null))
+            )]))
         );
         assert_eq!(
             schema.validate(json!([])),
-            Err(json_error(vec![type_error("", "", JsonType::Boolean)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!([]))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(1)),
-            Err(json_error(vec![type_error("", "", JsonType::Boolean)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!(1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!({})),
-            Err(json_error(vec![type_error("", "", JsonType::Boolean)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!({}))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Boolean)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!("foo"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_disables_string_coercion() {
+        let schema = boolean().coercions(|c| c.allow_string_to_boolean(false));
+        assert_eq!(schema.validate(json!(true)), Ok(json!(true)));
+        assert_eq!(
+            schema.validate(json!("true")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Boolean,
+                Some(&json!("true"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_coerces_zero_and_one_from_numbers() {
+        let schema = boolean().from_numbers();
+        assert_eq!(schema.validate(json!(1)), Ok(json!(true)));
+        assert_eq!(schema.validate(json!(0)), Ok(json!(false)));
+        assert_eq!(schema.validate(json!("1")), Ok(json!(true)));
+        assert_eq!(schema.validate(json!("0")), Ok(json!(false)));
+        assert_eq!(schema.validate(json!("true")), Ok(json!(true)));
+        for input in &[json!(2), json!(-1), json!(1.5), json!("2")] {
+            assert_eq!(
+                schema.validate(input.clone()),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Boolean,
+                    Some(input)
+                )])),
+                "{}",
+                input
+            );
+        }
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!(0)), Ok(json!(false)));
+        assert!(boolean().validate(json!(0)).is_err());
+        assert!(boolean().validate(json!("1")).is_err());
+    }
+
+    #[test]
+    fn it_coerces_truthy_strings() {
+        let schema = boolean().truthy_strings();
+        for (string, expected) in &[
+            ("true", true),
+            ("yes", true),
+            ("on", true),
+            ("false", false),
+            ("no", false),
+            ("off", false),
+            ("TRUE", true),
+            ("Yes", true),
+            ("oFF", false),
+        ] {
+            assert_eq!(schema.validate(json!(string)), Ok(json!(expected)));
+        }
+        for string in &["y", "1", "enabled", ""] {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Boolean,
+                    Some(&json!(string))
+                )]))
+            );
+        }
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!("no")), Ok(json!(false)));
+        for string in &["TRUE", "yes", "off"] {
+            assert_eq!(
+                boolean().validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Boolean,
+                    Some(&json!(string))
+                )]))
+            );
+        }
+    }
+
+    #[test]
+    fn it_runs_custom_tests() {
+        let schema =
+            boolean()
+                .label("Terms")
+                .test("accepted", "<label> must be accepted.", |accepted| {
+                    Ok(*accepted)
+                });
+        assert_eq!(schema.validate(json!(true)), Ok(json!(true)));
+        assert_eq!(
+            schema.validate(json!(false)),
+            Err(json_error(vec![test_error(
+                "accepted",
+                "",
+                "Terms must be accepted."
+            )
+            .with_label("Terms")]))
+        );
+    }
+
+    #[test]
+    fn it_fills_in_a_default_value() {
+        let schema = object().boolean("archived", |b| b.optional().default_value(false));
+        assert_eq!(schema.validate(json!({})), Ok(json!({ "archived": false })));
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(spec.properties["archived"].default, Some(json!(false)));
+    }
+
+    #[test]
+    fn it_requires_true_or_false() {
+        let schema = boolean().label("Terms").is_true();
+        assert_eq!(schema.validate(json!("true")), Ok(json!(true)));
+        assert_eq!(
+            schema.validate(json!(false)),
+            Err(json_error(vec![test_error(
+                "is_true",
+                "",
+                "Terms must be true."
+            )
+            .with_label("Terms")]))
+        );
+        let schema = from_spec(&boolean().is_false().to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!(false)), Ok(json!(false)));
+        assert!(schema.validate(json!(true)).is_err());
+    }
+
+    #[test]
+    fn it_tests_the_default_value() {
+        let schema = object().boolean("beta", |b| b.optional().default_value(false));
+        assert_eq!(schema.validate(json!({})), Ok(json!({ "beta": false })));
+        assert_eq!(
+            schema.validate(json!({ "beta": true })),
+            Ok(json!({ "beta": true }))
+        );
+        let schema = object().boolean("terms", |b| {
+            b.label("Terms").optional().default_value(false).is_true()
+        });
+        assert_eq!(
+            schema.validate(json!({})),
+            Err(json_error(vec![test_error(
+                "is_true",
+                "terms",
+                "Terms must be true."
+            )
+            .with_label("Terms")]))
+        );
+    }
+
+    #[test]
+    fn it_inverts_booleans() {
+        let schema = boolean().invert();
+        assert_eq!(schema.validate(json!(true)), Ok(json!(false)));
+        assert_eq!(schema.validate(json!(false)), Ok(json!(true)));
+        assert_eq!(schema.validate(json!("true")), Ok(json!(false)));
+        assert_eq!(schema.validate(json!("false")), Ok(json!(true)));
+        let schema = object().boolean("disabled", |b| b.label("Disabled").invert().is_true());
+        assert_eq!(
+            schema.validate(json!({ "disabled": "false" })),
+            Ok(json!({ "disabled": true }))
+        );
+        assert_eq!(
+            schema.validate(json!({ "disabled": true })),
+            Err(json_error(vec![test_error(
+                "is_true",
+                "disabled",
+                "Disabled must be true."
+            )
+            .with_label("Disabled")]))
+        );
+        let schema = from_spec(&boolean().invert().to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!(true)), Ok(json!(false)));
+    }
+
+    #[test]
+    fn it_rejects_strings_in_strict_mode() {
+        let schema = object().boolean("active", |b| b.label("Active").strict());
+        assert_eq!(
+            schema.validate(json!({ "active": true })),
+            Ok(json!({ "active": true }))
+        );
+        assert_eq!(
+            schema.validate(json!({ "active": "true" })),
+            Err(json_error(vec![type_error(
+                "active",
+                "Active",
+                JsonType::Boolean,
+                Some(&json!("true"))
+            )]))
+        );
+        let schema = boolean().from_numbers().truthy_strings().strict();
+        assert!(schema.validate(json!(1)).is_err());
+        assert!(schema.validate(json!("yes")).is_err());
+        let schema = object().boolean("active", |b| b);
+        assert_eq!(
+            schema.validate(json!({ "active": "true" })),
+            Ok(json!({ "active": true }))
         );
     }
 }
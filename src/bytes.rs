@@ -0,0 +1,366 @@
+use super::{
+    context::Context,
+    error::{internal_error, ValidationError, ValidationResult},
+    json::{from_json, Json, JsonType},
+    spec::{RuleSpec, SchemaSpec, SpecError},
+    OkSchema, TestOutcome, Validator,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Base64 {
+    #[default]
+    Standard,
+    UrlSafe,
+}
+
+impl Base64 {
+    pub fn is_standard(&self) -> bool {
+        self == &Base64::Standard
+    }
+
+    fn table(self) -> &'static [u8; 64] {
+        match self {
+            Base64::Standard => STANDARD,
+            Base64::UrlSafe => URL_SAFE,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Encoding {
+    Base64,
+    Array,
+}
+
+pub fn decode(text: &str, alphabet: Base64) -> Option<Vec<u8>> {
+    let trimmed = text.trim_end_matches('=');
+    let padding = text.len() - trimmed.len();
+    if padding > 2 || (padding > 0 && !text.len().is_multiple_of(4)) || trimmed.len() % 4 == 1 {
+        return None;
+    }
+    let table = alphabet.table();
+    let mut bytes = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for char in trimmed.bytes() {
+        let value = table.iter().position(|&symbol| symbol == char)?;
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    match buffer {
+        0 => Some(bytes),
+        _ => None,
+    }
+}
+
+pub fn encode(bytes: &[u8], alphabet: Base64) -> String {
+    let table = alphabet.table();
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | (byte as u32) << (16 - 8 * index)
+            });
+        let symbols = chunk.len() + 1;
+        (0..symbols)
+            .for_each(|index| text.push(table[(group >> (18 - 6 * index) & 63) as usize] as char));
+        if alphabet.is_standard() {
+            (symbols..4).for_each(|_| text.push('='));
+        }
+    }
+    text
+}
+
+pub struct BytesSchema {
+    validator: Validator<Vec<u8>>,
+    encoding: Encoding,
+}
+
+impl BytesSchema {
+    pub fn new() -> Self {
+        BytesSchema {
+            validator: Validator::new(JsonType::Bytes),
+            encoding: Encoding::Base64,
+        }
+    }
+
+    pub fn test<M, F, O>(mut self, name: &'static str, message: M, test: F) -> Self
+    where
+        M: Into<String>,
+        F: Fn(&Vec<u8>) -> std::result::Result<O, ValidationError> + 'static,
+        O: Into<TestOutcome>,
+    {
+        self.validator
+            .add_test(name, message, Json::Null, test)
+            .custom();
+        self
+    }
+
+    pub fn alphabet(mut self, alphabet: Base64) -> Self {
+        self.validator.coercions.base64 = alphabet;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn min_size(mut self, min: usize) -> Self {
+        self.validator
+            .add_test(
+                "min_size",
+                format!("<label> must contain at least {} bytes.", min),
+                json!({ "min": min }),
+                move |bytes| Ok(bytes.len() >= min),
+            )
+            .measure(|bytes| json!(bytes.len()));
+        self
+    }
+
+    pub fn max_size(mut self, max: usize) -> Self {
+        self.validator
+            .add_test(
+                "max_size",
+                format!("<label> may contain at most {} bytes.", max),
+                json!({ "max": max }),
+                move |bytes| Ok(bytes.len() <= max),
+            )
+            .measure(|bytes| json!(bytes.len()));
+        self
+    }
+
+    pub fn from_spec(spec: &SchemaSpec) -> Result<Self, SpecError> {
+        let mut schema = BytesSchema::new();
+        schema.validator.load_spec(spec);
+        let schema =
+            spec.transforms
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "to_array" => Ok(schema.encoding(Encoding::Array)),
+                    name => Err(SpecError::UnknownRule(name.into())),
+                })?;
+        let mut schema =
+            spec.tests
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "min_size" => Ok(schema.min_size(rule.param("min")?)),
+                    "max_size" => Ok(schema.max_size(rule.param("max")?)),
+                    name => Err(SpecError::UnknownRule(name.into())),
+                })?;
+        schema.validator.load_messages(&spec.tests);
+        Ok(schema)
+    }
+}
+
+impl OkSchema for BytesSchema {
+    fn label(mut self, label: &'static str) -> Self {
+        self.validator.label = Some(label.into());
+        self
+    }
+
+    fn desc(mut self, description: &'static str) -> Self {
+        self.validator.description = Some(description.into());
+        self
+    }
+
+    fn optional(mut self) -> Self {
+        self.validator.is_optional = true;
+        self
+    }
+
+    fn nullable(mut self) -> Self {
+        self.validator.is_nullable = true;
+        self
+    }
+
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
+    fn validate_at(
+        &self,
+        path: &str,
+        value: Option<Json>,
+        all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
+    ) -> ValidationResult<Option<Json>> {
+        let value = self.validator.exec(path, value, all_errors, context)?;
+        match (value, self.encoding) {
+            (Some(json @ Json::Array(_)), Encoding::Base64) => match from_json::<Vec<u8>>(json) {
+                Ok(bytes) => Ok(Some(Json::String(encode(
+                    &bytes,
+                    self.validator.coercions.base64,
+                )))),
+                Err(err) => {
+                    all_errors.push(internal_error(path, err));
+                    Err(())
+                }
+            },
+            (value, _) => Ok(value),
+        }
+    }
+
+    fn to_spec(&self) -> Result<SchemaSpec, SpecError> {
+        let mut spec = self.validator.to_spec()?;
+        if self.encoding == Encoding::Array {
+            spec.transforms.push(RuleSpec {
+                name: "to_array".into(),
+                params: Json::Null,
+                message: None,
+            });
+        }
+        Ok(spec)
+    }
+}
+
+pub fn bytes() -> BytesSchema {
+    BytesSchema::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        bytes,
+        error::{base64_error, byte_error, json_error, test_error, type_error},
+        from_spec,
+        json::JsonType,
+        object, Base64, Encoding, OkSchema,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn it_accepts_base64_and_byte_arrays() {
+        let schema = bytes();
+        assert_eq!(schema.validate(json!("aGk=")), Ok(json!("aGk=")));
+        assert_eq!(schema.validate(json!("aGk")), Ok(json!("aGk=")));
+        assert_eq!(schema.validate(json!([104, 105])), Ok(json!("aGk=")));
+        assert_eq!(schema.validate(json!([])), Ok(json!("")));
+        assert_eq!(
+            schema.validate(json!(true)),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Bytes,
+                Some(&json!(true))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_normalizes_to_byte_arrays() {
+        let schema = bytes().encoding(Encoding::Array);
+        assert_eq!(schema.validate(json!("/+8=")), Ok(json!([255, 239])));
+        assert_eq!(schema.validate(json!([1, 2, 3])), Ok(json!([1, 2, 3])));
+    }
+
+    #[test]
+    fn it_uses_the_url_safe_alphabet() {
+        let schema = bytes().alphabet(Base64::UrlSafe);
+        assert_eq!(schema.validate(json!("_-8")), Ok(json!("_-8")));
+        assert_eq!(schema.validate(json!([255, 239])), Ok(json!("_-8")));
+        assert_eq!(
+            schema.validate(json!("/+8=")),
+            Err(json_error(vec![base64_error("", "", &json!("/+8="))]))
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_base64() {
+        let schema = bytes().label("Avatar");
+        ["a", "aGk==", "a=Gk", "aGl=", "!!!!"]
+            .iter()
+            .for_each(|string| {
+                assert_eq!(
+                    schema.validate(json!(string)),
+                    Err(json_error(vec![base64_error("", "Avatar", &json!(string))]))
+                );
+            });
+    }
+
+    #[test]
+    fn it_rejects_invalid_bytes() {
+        let schema = object().bytes("avatar", |avatar| avatar);
+        assert_eq!(
+            schema.validate(json!({ "avatar": [1, 256, -1] })),
+            Err(json_error(vec![byte_error(
+                "avatar[1]",
+                "avatar[1]",
+                &json!(256)
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!({ "avatar": [1, "2"] })),
+            Err(json_error(vec![byte_error(
+                "avatar[1]",
+                "avatar[1]",
+                &json!("2")
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_limits_the_decoded_size() {
+        let schema = bytes().label("Avatar").min_size(2).max_size(3);
+        assert_eq!(schema.validate(json!("AAAA")), Ok(json!("AAAA")));
+        assert_eq!(
+            schema.validate(json!("AA")),
+            Err(json_error(vec![test_error(
+                "min_size",
+                "",
+                "Avatar must contain at least 2 bytes."
+            )
+            .with_label("Avatar")
+            .with_measure(json!({ "min": 2 }), json!(1))]))
+        );
+        assert_eq!(
+            schema.validate(json!([0, 0, 0, 0])),
+            Err(json_error(vec![test_error(
+                "max_size",
+                "",
+                "Avatar may contain at most 3 bytes."
+            )
+            .with_label("Avatar")
+            .with_measure(json!({ "max": 3 }), json!(4))]))
+        );
+    }
+
+    #[test]
+    fn it_round_trips_specs() {
+        let schema = bytes()
+            .alphabet(Base64::UrlSafe)
+            .encoding(Encoding::Array)
+            .max_size(2);
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            json!({
+                "type": "bytes",
+                "coercions": { "base64": "url_safe" },
+                "transforms": [{ "name": "to_array" }],
+                "tests": [{ "name": "max_size", "params": { "max": 2 } }]
+            })
+        );
+        let schema = from_spec(&spec).unwrap();
+        assert_eq!(schema.validate(json!("_-8")), Ok(json!([255, 239])));
+    }
+}
@@ -0,0 +1,420 @@
+use super::{
+    error::{timeout_error, PathSegment, ValidationError, REDACTED},
+    json::{Json, JsonType},
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+pub trait ValidationObserver {
+    fn on_enter(&mut self, _path: &str, _json_type: JsonType) {}
+
+    fn on_test(&mut self, _path: &str, _test_type: &str, _passed: bool) {}
+
+    fn on_coercion(&mut self, _path: &str, _from: JsonType, _to: JsonType) {}
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum PathFormat {
+    #[default]
+    Dotted,
+    JsonPointer,
+}
+
+#[derive(Default)]
+pub struct Options {
+    pub report_coercions: bool,
+    pub path_format: PathFormat,
+    pub include_descriptions: bool,
+    pub deny_duplicate_keys: bool,
+    pub time_budget: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Coercion {
+    pub path: String,
+    pub kind: &'static str,
+    pub from: Json,
+    pub to: Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Validated {
+    pub value: Json,
+    pub coercions: Vec<Coercion>,
+}
+
+pub struct Context<'a> {
+    observer: Option<&'a mut dyn ValidationObserver>,
+    coercions: Option<Vec<Coercion>>,
+    segments: Vec<PathSegment>,
+    resolving: Vec<(String, usize)>,
+    include_descriptions: bool,
+    budget: Option<Budget>,
+}
+
+struct Budget {
+    started: Instant,
+    limit: Duration,
+    processed: usize,
+    exceeded: bool,
+}
+
+impl<'a> Context<'a> {
+    pub fn new() -> Self {
+        Context {
+            observer: None,
+            coercions: None,
+            segments: vec![],
+            resolving: vec![],
+            include_descriptions: false,
+            budget: None,
+        }
+    }
+
+    pub fn with_observer(observer: &'a mut dyn ValidationObserver) -> Self {
+        Context {
+            observer: Some(observer),
+            ..Context::new()
+        }
+    }
+
+    pub fn with_options(options: &Options) -> Self {
+        Context {
+            coercions: if options.report_coercions {
+                Some(vec![])
+            } else {
+                None
+            },
+            include_descriptions: options.include_descriptions,
+            budget: options.time_budget.map(|limit| Budget {
+                started: Instant::now(),
+                limit,
+                processed: 0,
+                exceeded: false,
+            }),
+            ..Context::new()
+        }
+    }
+
+    pub fn includes_descriptions(&self) -> bool {
+        self.include_descriptions
+    }
+
+    pub fn reports_coercions(&self) -> bool {
+        self.coercions.is_some()
+    }
+
+    pub fn report(&mut self, path: &str, kind: &'static str, from: Json, to: Json, redact: bool) {
+        if let Some(coercions) = self.coercions.as_mut() {
+            if from == to {
+                return;
+            }
+            let (from, to) = match redact {
+                true => (Json::from(REDACTED), Json::from(REDACTED)),
+                false => (from, to),
+            };
+            coercions.push(Coercion {
+                path: path.into(),
+                kind,
+                from,
+                to,
+            });
+        }
+    }
+
+    pub fn exhausted(&mut self, path: &str, errors: &mut Vec<ValidationError>) -> bool {
+        let budget = match self.budget.as_mut() {
+            Some(budget) => budget,
+            None => return false,
+        };
+        if budget.exceeded {
+            return true;
+        }
+        if budget.started.elapsed() > budget.limit {
+            budget.exceeded = true;
+            errors.push(timeout_error(path, budget.limit, budget.processed));
+            return true;
+        }
+        budget.processed += 1;
+        false
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) {
+        self.segments.pop();
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Marks a reference as resolving, failing if it is already resolving at this depth.
+    pub fn resolve(&mut self, name: &str) -> bool {
+        let depth = self.segments.len();
+        if self.resolving.contains(&(name.to_string(), depth)) {
+            return false;
+        }
+        self.resolving.push((name.into(), depth));
+        true
+    }
+
+    pub fn resolved(&mut self) {
+        self.resolving.pop();
+    }
+
+    pub fn into_coercions(self) -> Vec<Coercion> {
+        self.coercions.unwrap_or_default()
+    }
+
+    pub fn enter(&mut self, path: &str, json_type: JsonType) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_enter(path, json_type);
+        }
+    }
+
+    pub fn test(&mut self, path: &str, test_type: &str, passed: bool) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_test(path, test_type, passed);
+        }
+    }
+
+    pub fn coercion(&mut self, path: &str, from: JsonType, to: JsonType) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_coercion(path, from, to);
+        }
+    }
+}
+
+impl Default for Context<'_> {
+    fn default() -> Self {
+        Context::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        array,
+        error::{json_error, test_error},
+        integer,
+        json::JsonType,
+        object, Coercion, OkSchema, Options, PathFormat, Validated, ValidationObserver,
+    };
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Enter(String, JsonType),
+        Test(String, String, bool),
+        Coercion(String, JsonType, JsonType),
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<Event>,
+    }
+
+    impl ValidationObserver for Recorder {
+        fn on_enter(&mut self, path: &str, json_type: JsonType) {
+            self.events.push(Event::Enter(path.into(), json_type));
+        }
+
+        fn on_test(&mut self, path: &str, test_type: &str, passed: bool) {
+            self.events
+                .push(Event::Test(path.into(), test_type.into(), passed));
+        }
+
+        fn on_coercion(&mut self, path: &str, from: JsonType, to: JsonType) {
+            self.events.push(Event::Coercion(path.into(), from, to));
+        }
+    }
+
+    #[test]
+    fn it_observes_a_nested_validation() {
+        let schema = object().array("scores", |scores| scores.min_length(1).of(integer().min(2)));
+        let mut recorder = Recorder::default();
+        assert!(schema
+            .validate_with_observer(json!({ "scores": ["3", 1] }), &mut recorder)
+            .is_err());
+        assert_eq!(
+            recorder.events,
+            vec![
+                Event::Enter("".into(), JsonType::Object),
+                Event::Enter("scores".into(), JsonType::Array),
+                Event::Test("scores".into(), "min_length".into(), true),
+                Event::Enter("scores[0]".into(), JsonType::Integer),
+                Event::Coercion("scores[0]".into(), JsonType::String, JsonType::Integer),
+                Event::Test("scores[0]".into(), "min".into(), true),
+                Event::Enter("scores[1]".into(), JsonType::Integer),
+                Event::Test("scores[1]".into(), "min".into(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_observes_type_errors_without_tests() {
+        let schema = array().of(integer());
+        let mut recorder = Recorder::default();
+        assert!(schema
+            .validate_with_observer(json!([true]), &mut recorder)
+            .is_err());
+        assert_eq!(
+            recorder.events,
+            vec![
+                Event::Enter("".into(), JsonType::Array),
+                Event::Enter("[0]".into(), JsonType::Integer),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_coercions_and_transforms() {
+        let schema = object()
+            .integer("age", |age| age)
+            .string("name", |name| name.trim());
+        let options = Options {
+            report_coercions: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            schema.validate_with(json!({ "age": "42", "name": "foo" }), options),
+            Ok(Validated {
+                value: json!({ "age": 42, "name": "foo" }),
+                coercions: vec![Coercion {
+                    path: "age".into(),
+                    kind: "coercion",
+                    from: json!("42"),
+                    to: json!(42)
+                }]
+            })
+        );
+        let schema = array().of(object().string("name", |name| name.trim()));
+        let options = Options {
+            report_coercions: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            schema.validate_with(json!([{ "name": " foo " }, { "name": 1 }]), options),
+            Ok(Validated {
+                value: json!([{ "name": "foo" }, { "name": "1" }]),
+                coercions: vec![
+                    Coercion {
+                        path: "[0].name".into(),
+                        kind: "trim",
+                        from: json!(" foo "),
+                        to: json!("foo")
+                    },
+                    Coercion {
+                        path: "[1].name".into(),
+                        kind: "coercion",
+                        from: json!(1),
+                        to: json!("1")
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_redacts_sensitive_values_in_reports() {
+        let schema = object()
+            .string("password", |password| password.sensitive().trim())
+            .string("username", |username| username.trim());
+        let options = Options {
+            report_coercions: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            schema.validate_with(
+                json!({ "password": " hunter2 ", "username": " foo " }),
+                options
+            ),
+            Ok(Validated {
+                value: json!({ "password": "hunter2", "username": "foo" }),
+                coercions: vec![
+                    Coercion {
+                        path: "password".into(),
+                        kind: "trim",
+                        from: json!("[redacted]"),
+                        to: json!("[redacted]")
+                    },
+                    Coercion {
+                        path: "username".into(),
+                        kind: "trim",
+                        from: json!(" foo "),
+                        to: json!("foo")
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_includes_descriptions_on_request() {
+        let schema = object()
+            .integer("age", |age| age.desc("Age in whole years.").min(18))
+            .string("email", |email| email.desc("A contact address."))
+            .string("name", |name| name.min_length(1));
+        let payload = json!({ "age": 17, "email": [], "name": "" });
+        let options = Options {
+            include_descriptions: true,
+            ..Options::default()
+        };
+        let error = schema.validate_with(payload.clone(), options).unwrap_err();
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| (error.path(), error.description()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("age", Some("Age in whole years.")),
+                ("email", Some("A contact address.")),
+                ("name", None),
+            ]
+        );
+        let serialized = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            serialized["errors"][0]["description"],
+            json!("Age in whole years.")
+        );
+        assert_eq!(serialized["errors"][2].get("description"), None);
+        let error = schema.validate(payload).unwrap_err();
+        assert!(error.flatten().all(|error| error.description().is_none()));
+    }
+
+    #[test]
+    fn it_omits_the_report_by_default() {
+        let schema = integer();
+        assert_eq!(
+            schema.validate_with(json!("42"), Options::default()),
+            Ok(Validated {
+                value: json!(42),
+                coercions: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn it_formats_error_paths_as_json_pointers() {
+        let schema = object().array("a/b", |a| {
+            a.of(array().of(object().integer("c~d", |c| c.min(1))))
+        });
+        let options = Options {
+            path_format: PathFormat::JsonPointer,
+            ..Options::default()
+        };
+        assert_eq!(
+            schema.validate_with(json!({ "a/b": [[], [{ "c~d": 0 }]] }), options),
+            Err(json_error(vec![test_error(
+                "min",
+                "a/b[1][0].c~d",
+                "a/b[1][0].c~d must be at least 1."
+            )
+            .with_measure(json!({ "min": 1 }), json!(0))])
+            .with_json_pointer_paths())
+        );
+    }
+}
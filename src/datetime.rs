@@ -0,0 +1,170 @@
+pub fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() != 10 || !text.is_ascii() || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    match (
+        digits(&text[0..4]),
+        digits(&text[5..7]),
+        digits(&text[8..10]),
+    ) {
+        (Some(year), Some(month), Some(day)) => day >= 1 && day <= days_in_month(year, month),
+        _ => false,
+    }
+}
+
+pub fn is_time(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if !matches!(bytes.len(), 5 | 8) || !text.is_ascii() || bytes[2] != b':' {
+        return false;
+    }
+    let second = match &text[5..] {
+        "" => Some(0),
+        seconds if bytes[5] == b':' => digits(&seconds[1..]),
+        _ => None,
+    };
+    match (digits(&text[0..2]), digits(&text[3..5]), second) {
+        (Some(24), Some(0), Some(0)) => true,
+        (Some(hour), Some(minute), Some(second)) => hour <= 23 && minute <= 59 && second <= 59,
+        _ => false,
+    }
+}
+
+pub fn is_datetime(text: &str) -> bool {
+    if !text.is_ascii() || text.len() < 20 || !matches!(&text[10..11], "T" | "t") {
+        return false;
+    }
+    let rest = &text[11..];
+    let time = match rest.strip_suffix(&['Z', 'z'][..]) {
+        Some(time) => time,
+        None if rest.len() >= 6 && is_offset(&rest[rest.len() - 6..]) => &rest[..rest.len() - 6],
+        None => return false,
+    };
+    is_date(&text[..10]) && is_partial_time(time)
+}
+
+pub fn normalize_datetime(text: &str) -> Option<String> {
+    if !is_datetime(text) {
+        return None;
+    }
+    let normalized = text.to_ascii_uppercase();
+    match normalized.strip_suffix("+00:00") {
+        Some(local) => Some(format!("{}Z", local)),
+        None => Some(normalized),
+    }
+}
+
+fn is_partial_time(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return false;
+    }
+    let fraction = match &text[8..] {
+        "" => true,
+        fraction => fraction
+            .strip_prefix('.')
+            .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())),
+    };
+    match (
+        digits(&text[0..2]),
+        digits(&text[3..5]),
+        digits(&text[6..8]),
+    ) {
+        (Some(hour), Some(minute), Some(second)) => {
+            fraction && hour <= 23 && minute <= 59 && second <= 60
+        }
+        _ => false,
+    }
+}
+
+fn is_offset(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if !matches!(bytes[0], b'+' | b'-') || bytes[3] != b':' {
+        return false;
+    }
+    match (digits(&text[1..3]), digits(&text[4..6])) {
+        (Some(hour), Some(minute)) => hour <= 23 && minute <= 59,
+        _ => false,
+    }
+}
+
+fn digits(text: &str) -> Option<u32> {
+    match text.bytes().all(|b| b.is_ascii_digit()) {
+        true => text.parse().ok(),
+        false => None,
+    }
+}
+
+fn is_leap_year(year: u32) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_date, is_datetime, is_time, normalize_datetime};
+
+    #[test]
+    fn it_checks_calendar_dates() {
+        assert!(is_date("2024-02-29"));
+        assert!(is_date("2000-02-29"));
+        assert!(!is_date("1900-02-29"));
+        assert!(!is_date("2023-02-29"));
+        assert!(!is_date("2021-04-31"));
+        assert!(!is_date("2021-13-01"));
+        assert!(!is_date("2021-00-10"));
+        assert!(!is_date("2021-1-01"));
+        assert!(!is_date("２０２１-01-01"));
+    }
+
+    #[test]
+    fn it_checks_times_of_day() {
+        assert!(is_time("09:30"));
+        assert!(is_time("23:59:59"));
+        assert!(is_time("24:00"));
+        assert!(is_time("24:00:00"));
+        assert!(!is_time("24:00:01"));
+        assert!(!is_time("24:01"));
+        assert!(!is_time("12:60"));
+        assert!(!is_time("12:30:60"));
+        assert!(!is_time("9:30"));
+        assert!(!is_time("09:30:"));
+        assert!(!is_time("09-30-00"));
+    }
+
+    #[test]
+    fn it_checks_timestamps() {
+        assert!(is_datetime("2023-06-01T12:30:00Z"));
+        assert!(is_datetime("2023-06-01t12:30:00.123456z"));
+        assert!(is_datetime("2023-06-01T12:30:00-07:00"));
+        assert!(is_datetime("2016-12-31T23:59:60Z"));
+        assert!(!is_datetime("2023-06-01T12:30:00"));
+        assert!(!is_datetime("2023-06-01T12:30:00."));
+        assert!(!is_datetime("2023-06-01 12:30:00Z"));
+        assert!(!is_datetime("2023-06-01T24:00:00Z"));
+        assert!(!is_datetime("2023-06-01T12:30:00+24:00"));
+        assert!(!is_datetime("2023-06-01T12:30Z"));
+    }
+
+    #[test]
+    fn it_normalizes_timestamps() {
+        assert_eq!(
+            normalize_datetime("2023-06-01t12:30:00.5+00:00"),
+            Some("2023-06-01T12:30:00.5Z".into())
+        );
+        assert_eq!(
+            normalize_datetime("2023-06-01t12:30:00z"),
+            Some("2023-06-01T12:30:00Z".into())
+        );
+        assert_eq!(normalize_datetime("2023-06-01"), None);
+    }
+}
@@ -0,0 +1,666 @@
+use super::{
+    context::Context,
+    error::{json_error, PathSegment, ValidationError},
+    json::Json,
+    OkSchema,
+};
+use serde::de::{
+    self,
+    value::{MapAccessDeserializer, SeqAccessDeserializer},
+    DeserializeSeed, Deserializer, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::Deserialize;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+pub enum Streaming<'a> {
+    Buffered,
+    Elements {
+        schema: &'a dyn OkSchema,
+        sensitive: bool,
+    },
+    Properties {
+        schemas: &'a BTreeMap<String, Box<dyn OkSchema>>,
+        sensitive: bool,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeserializeError<E> {
+    Invalid(ValidationError),
+    Deserialize(E),
+}
+
+/// Validates while deserializing, stopping at the first invalid value in the input.
+///
+/// The error holds every failure of the object that value belongs to, so its first
+/// leaf is the first leaf `validate` would report for the same input.
+pub fn validate_deserialize<'de, T, D>(
+    schema: &dyn OkSchema,
+    deserializer: D,
+) -> Result<T, DeserializeError<D::Error>>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let errors = RefCell::new(vec![]);
+    let result = T::deserialize(Validating {
+        schema,
+        path: String::new(),
+        segments: vec![],
+        sensitive: false,
+        errors: &errors,
+        inner: deserializer,
+    });
+    let errors = errors.into_inner();
+    if !errors.is_empty() {
+        return Err(DeserializeError::Invalid(json_error(errors)));
+    }
+    result.map_err(DeserializeError::Deserialize)
+}
+
+struct Node<'a> {
+    schema: &'a dyn OkSchema,
+    path: String,
+    segments: Vec<PathSegment>,
+    sensitive: bool,
+    errors: &'a RefCell<Vec<ValidationError>>,
+}
+
+impl<'a> Node<'a> {
+    fn check<E: de::Error>(&self, value: Option<Json>) -> Result<Json, E> {
+        self.resolve(value).map(|json| json.unwrap_or(Json::Null))
+    }
+
+    fn resolve<E: de::Error>(&self, value: Option<Json>) -> Result<Option<Json>, E> {
+        let mut errors = vec![];
+        let mut context = Context::new();
+        self.segments
+            .iter()
+            .for_each(|segment| context.push(segment.clone()));
+        let validated = self
+            .schema
+            .validate_at(&self.path, value, &mut errors, &mut context);
+        match validated {
+            Ok(json) => Ok(json),
+            Err(_) => {
+                let errors = errors.into_iter().map(|error| match self.sensitive {
+                    true => error.redacted(),
+                    false => error,
+                });
+                self.errors.borrow_mut().extend(errors);
+                Err(E::custom("validation failed"))
+            }
+        }
+    }
+
+    fn child(&self, schema: &'a dyn OkSchema, path: String, segment: PathSegment) -> Node<'a> {
+        let mut segments = self.segments.clone();
+        segments.push(segment);
+        Node {
+            schema,
+            path,
+            segments,
+            sensitive: self.sensitive,
+            errors: self.errors,
+        }
+    }
+
+    /// Streamed descendants of a sensitive container are redacted like buffered ones.
+    fn within(mut self, sensitive: bool) -> Self {
+        self.sensitive |= sensitive;
+        self
+    }
+
+    fn failures(&self) -> usize {
+        self.errors.borrow().len()
+    }
+
+    fn key_path(&self, key: &str) -> String {
+        match self.path.as_str() {
+            "" => key.to_string(),
+            path => format!("{}.{}", path, key),
+        }
+    }
+}
+
+struct Validating<'a, D> {
+    schema: &'a dyn OkSchema,
+    path: String,
+    segments: Vec<PathSegment>,
+    sensitive: bool,
+    errors: &'a RefCell<Vec<ValidationError>>,
+    inner: D,
+}
+
+impl<'a, D> Validating<'a, D> {
+    fn split(self) -> (Node<'a>, D) {
+        let node = Node {
+            schema: self.schema,
+            path: self.path,
+            segments: self.segments,
+            sensitive: self.sensitive,
+            errors: self.errors,
+        };
+        (node, self.inner)
+    }
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Validating<'_, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let (node, inner) = self.split();
+        match node.schema.streaming() {
+            Streaming::Buffered => {
+                let json = Json::deserialize(inner)?;
+                let validated = node.check(Some(json))?;
+                validated
+                    .deserialize_any(visitor)
+                    .map_err(de::Error::custom)
+            }
+            _ => inner.deserialize_any(Streamed { node, visitor }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let (node, inner) = self.split();
+        inner.deserialize_option(Optional { node, visitor })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Optional<'a, V> {
+    node: Node<'a>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Optional<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        match self.node.check(Some(Json::Null))? {
+            Json::Null => self.visitor.visit_none(),
+            validated => validated
+                .deserialize_option(self.visitor)
+                .map_err(E::custom),
+        }
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.visitor.visit_some(Validating {
+            schema: self.node.schema,
+            path: self.node.path,
+            segments: self.node.segments,
+            sensitive: self.node.sensitive,
+            errors: self.node.errors,
+            inner: deserializer,
+        })
+    }
+}
+
+struct Streamed<'a, V> {
+    node: Node<'a>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Streamed<'_, V> {
+    fn scalar<E: de::Error>(self, json: Json) -> Result<V::Value, E> {
+        let validated = self.node.check(Some(json))?;
+        validated.deserialize_any(self.visitor).map_err(E::custom)
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Streamed<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<V::Value, E> {
+        self.scalar(Json::from(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<V::Value, E> {
+        self.scalar(Json::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<V::Value, E> {
+        self.scalar(Json::from(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<V::Value, E> {
+        self.scalar(Json::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<V::Value, E> {
+        self.scalar(Json::from(value))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.scalar(Json::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        match self.node.schema.streaming() {
+            Streaming::Elements { schema, sensitive } => {
+                let elements = StreamedSeq {
+                    node: self.node.within(sensitive),
+                    element: schema,
+                    index: 0,
+                    seq,
+                };
+                self.visitor.visit_seq(elements)
+            }
+            _ => {
+                let json = Json::deserialize(SeqAccessDeserializer::new(seq))?;
+                self.scalar(json)
+            }
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        match self.node.schema.streaming() {
+            Streaming::Properties { schemas, sensitive } => {
+                let entries = StreamedMap {
+                    start: self.node.failures(),
+                    node: self.node.within(sensitive),
+                    properties: schemas,
+                    seen: HashSet::new(),
+                    current: None,
+                    defaults: None,
+                    map,
+                };
+                self.visitor.visit_map(entries)
+            }
+            _ => {
+                let json = Json::deserialize(MapAccessDeserializer::new(map))?;
+                self.scalar(json)
+            }
+        }
+    }
+}
+
+struct Seed<'a, S> {
+    node: Node<'a>,
+    seed: S,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Seed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.seed.deserialize(Validating {
+            schema: self.node.schema,
+            path: self.node.path,
+            segments: self.node.segments,
+            sensitive: self.node.sensitive,
+            errors: self.node.errors,
+            inner: deserializer,
+        })
+    }
+}
+
+struct StreamedSeq<'a, A> {
+    node: Node<'a>,
+    element: &'a dyn OkSchema,
+    index: usize,
+    seq: A,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for StreamedSeq<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, A::Error> {
+        let path = format!("{}[{}]", self.node.path, self.index);
+        let node = self
+            .node
+            .child(self.element, path, PathSegment::Index(self.index));
+        self.index += 1;
+        self.seq.next_element_seed(Seed { node, seed })
+    }
+}
+
+enum Entry<'a> {
+    Streamed(Node<'a>),
+    Default(Json),
+}
+
+struct StreamedMap<'a, A> {
+    node: Node<'a>,
+    properties: &'a BTreeMap<String, Box<dyn OkSchema>>,
+    seen: HashSet<String>,
+    current: Option<Entry<'a>>,
+    defaults: Option<std::vec::IntoIter<(String, Json)>>,
+    start: usize,
+    map: A,
+}
+
+impl<'de, 'a, A: MapAccess<'de>> StreamedMap<'a, A> {
+    fn property(&self, key: &str, schema: &'a dyn OkSchema) -> Node<'a> {
+        let segment = PathSegment::Key(key.to_string());
+        self.node.child(schema, self.node.key_path(key), segment)
+    }
+
+    /// Validates the properties missing from the input, keeping their defaults.
+    fn missing<E: de::Error>(&self) -> Result<Vec<(String, Json)>, E> {
+        let before = self.node.failures();
+        let defaults = self
+            .properties
+            .iter()
+            .filter(|(key, _)| !self.seen.contains(*key))
+            .filter_map(|(key, schema)| {
+                match self.property(key, schema.as_ref()).resolve::<E>(None) {
+                    Ok(Some(json)) => Some((key.clone(), json)),
+                    _ => None,
+                }
+            })
+            .collect();
+        match self.node.failures() > before {
+            true => Err(E::custom("validation failed")),
+            false => Ok(defaults),
+        }
+    }
+
+    /// Validates the rest of the object after a failure so that every error in it
+    /// is reported, in the same order as buffered validation.
+    fn drain(&mut self) -> Result<(), A::Error> {
+        while let Some(key) = self.map.next_key::<String>()? {
+            match self.properties.get(&key) {
+                Some(schema) if self.seen.insert(key.clone()) => {
+                    let json = self.map.next_value::<Json>()?;
+                    let _ = self
+                        .property(&key, schema.as_ref())
+                        .check::<A::Error>(Some(json));
+                }
+                _ => {
+                    self.map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let _ = self.missing::<A::Error>();
+        let depth = self.node.segments.len();
+        self.node.errors.borrow_mut()[self.start..].sort_by_key(|error| {
+            match error.segments().get(depth) {
+                Some(PathSegment::Key(key)) => Some(key.clone()),
+                _ => None,
+            }
+        });
+        Ok(())
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for StreamedMap<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        if self.defaults.is_none() {
+            while let Some(key) = self.map.next_key::<String>()? {
+                match self.properties.get(&key) {
+                    Some(schema) => {
+                        self.current = Some(Entry::Streamed(self.property(&key, schema.as_ref())));
+                        self.seen.insert(key.clone());
+                        let key = IntoDeserializer::<A::Error>::into_deserializer(key);
+                        return seed.deserialize(key).map(Some);
+                    }
+                    None => {
+                        self.map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            self.defaults = Some(self.missing()?.into_iter());
+        }
+        match self.defaults.as_mut().and_then(Iterator::next) {
+            Some((key, json)) => {
+                self.current = Some(Entry::Default(json));
+                let key = IntoDeserializer::<A::Error>::into_deserializer(key);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+        match self.current.take() {
+            Some(Entry::Streamed(node)) => {
+                let before = self.node.failures();
+                let value = self.map.next_value_seed(Seed { node, seed });
+                if value.is_err() && self.node.failures() > before {
+                    self.drain()?;
+                }
+                value
+            }
+            Some(Entry::Default(json)) => seed.deserialize(json).map_err(de::Error::custom),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        array, de::validate_deserialize, object, DeserializeError, OkSchema, ValidationError,
+    };
+    use serde::Deserialize;
+    use serde_json::{json, Deserializer};
+    use std::io::Read;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: u64,
+        name: String,
+        note: Option<String>,
+    }
+
+    fn invalid<T>(
+        result: Result<T, DeserializeError<serde_json::Error>>,
+    ) -> Option<ValidationError> {
+        match result {
+            Err(DeserializeError::Invalid(error)) => Some(error),
+            _ => None,
+        }
+    }
+
+    fn schema() -> impl OkSchema {
+        array().of(object()
+            .unsigned("id", |id| id.min(1))
+            .string("name", |name| name.trim().min_length(1))
+            .string("note", |note| note.optional().nullable()))
+    }
+
+    #[test]
+    fn it_deserializes_valid_input() {
+        let input = r#"[{ "id": "1", "name": " foo ", "extra": true }, { "id": 2, "name": "bar", "note": null }]"#;
+        let items =
+            validate_deserialize::<Vec<Item>, _>(&schema(), &mut Deserializer::from_str(input));
+        assert_eq!(
+            items.ok(),
+            Some(vec![
+                Item {
+                    id: 1,
+                    name: "foo".into(),
+                    note: None
+                },
+                Item {
+                    id: 2,
+                    name: "bar".into(),
+                    note: None
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn it_reports_the_same_errors_as_values() {
+        let schema = schema();
+        let input = json!([
+            { "id": 1, "name": "foo" },
+            { "id": 2, "name": "bar", "note": "baz" },
+            { "id": 3, "name": "  " }
+        ]);
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema,
+            &mut Deserializer::from_str(&input.to_string()),
+        );
+        assert_eq!(invalid(streamed), schema.validate(input).err());
+        let input = json!([{ "id": 0, "name": "foo" }]);
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema,
+            &mut Deserializer::from_str(&input.to_string()),
+        );
+        assert_eq!(invalid(streamed), schema.validate(input).err());
+        let input = json!([{ "id": 1 }]);
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema,
+            &mut Deserializer::from_str(&input.to_string()),
+        );
+        assert_eq!(invalid(streamed), schema.validate(input).err());
+        for input in &[
+            r#"[{ "id": 0, "name": "" }]"#,
+            r#"[{ "name": "", "extra": 1, "id": 0 }]"#,
+            r#"[{ "note": 1 }]"#,
+        ] {
+            let streamed =
+                validate_deserialize::<Vec<Item>, _>(&schema, &mut Deserializer::from_str(input));
+            let input = serde_json::from_str(input).unwrap();
+            assert_eq!(invalid(streamed), schema.validate(input).err());
+        }
+    }
+
+    #[test]
+    fn it_reports_the_first_error_of_large_inputs() {
+        let schema = schema();
+        let input = (0..5_000)
+            .map(|id| match id % 3 {
+                0 => json!({ "name": "", "id": id }),
+                1 => json!({ "id": id, "name": "item", "note": id }),
+                _ => json!({ "id": id, "name": "item" }),
+            })
+            .collect::<Vec<_>>();
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema,
+            &mut Deserializer::from_str(&json!(input).to_string()),
+        );
+        let streamed = invalid(streamed).unwrap();
+        let buffered = schema.validate(json!(input)).unwrap_err();
+        assert!(buffered.flatten().count() > 2);
+        assert_eq!(streamed.flatten().count(), 2);
+        assert_eq!(streamed.flatten().next(), buffered.flatten().next());
+    }
+
+    #[test]
+    fn it_fills_defaults_of_missing_properties() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Flagged {
+            id: u64,
+            flag: Option<bool>,
+        }
+        let schema = object()
+            .unsigned("id", |id| id)
+            .boolean("flag", |flag| flag.optional().default_value(true));
+        let streamed = validate_deserialize::<serde_json::Value, _>(
+            &schema,
+            &mut Deserializer::from_str(r#"{ "id": 1 }"#),
+        );
+        assert_eq!(streamed.ok(), schema.validate(json!({ "id": 1 })).ok());
+        let streamed = validate_deserialize::<Flagged, _>(
+            &schema,
+            &mut Deserializer::from_str(r#"{ "id": 1 }"#),
+        );
+        assert_eq!(
+            streamed.ok(),
+            Some(Flagged {
+                id: 1,
+                flag: Some(true)
+            })
+        );
+    }
+
+    #[test]
+    fn it_redacts_elements_of_sensitive_containers() {
+        let schema = object().array("secrets", |secrets| {
+            secrets
+                .sensitive()
+                .of(object().unsigned("pin", |pin| pin.max(9999)))
+        });
+        for (input, secret) in &[
+            (
+                json!({ "secrets": [{ "pin": 1 }, { "pin": "hunter2" }] }),
+                "hunter2",
+            ),
+            (json!({ "secrets": [{ "pin": 12345 }] }), "12345"),
+        ] {
+            let streamed = validate_deserialize::<serde_json::Value, _>(
+                &schema,
+                &mut Deserializer::from_str(&input.to_string()),
+            );
+            let error = invalid(streamed).unwrap();
+            assert!(!serde_json::to_string(&error).unwrap().contains(secret));
+            assert_eq!(Some(error), schema.validate(input.clone()).err());
+        }
+    }
+
+    struct CountingReader<'a> {
+        bytes: &'a [u8],
+        read: usize,
+    }
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let count = (&self.bytes[self.read..]).read(buf)?;
+            self.read += count;
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn it_stops_reading_at_the_first_failure() {
+        let mut input = String::from(r#"[{ "id": 1, "name": "" }"#);
+        (2..10_000)
+            .for_each(|id| input.push_str(&format!(r#", {{ "id": {}, "name": "item" }}"#, id)));
+        input.push(']');
+        let mut reader = CountingReader {
+            bytes: input.as_bytes(),
+            read: 0,
+        };
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema(),
+            &mut Deserializer::from_reader(&mut reader),
+        );
+        assert!(matches!(streamed, Err(DeserializeError::Invalid(_))));
+        assert!(reader.read < 100);
+        assert!(input.len() > 250_000);
+    }
+
+    #[test]
+    fn it_surfaces_deserialization_errors() {
+        let streamed = validate_deserialize::<Vec<Item>, _>(
+            &schema(),
+            &mut Deserializer::from_str(r#"[{ "id": 1, "name": "foo" "#),
+        );
+        assert!(matches!(streamed, Err(DeserializeError::Deserialize(_))));
+    }
+}
@@ -0,0 +1,318 @@
+use super::{
+    json::Json,
+    spec::{RuleSpec, SchemaSpec, SpecError},
+    OkSchema,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    KeyAdded,
+    KeyRemoved,
+    TypeChanged,
+    MadeOptional,
+    MadeRequired,
+    MadeNullable,
+    MadeNonNullable,
+    BoundWidened,
+    BoundNarrowed,
+    ValuesAdded,
+    ValuesRemoved,
+    RuleAdded,
+    RuleRemoved,
+    RuleChanged,
+    TransformsChanged,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub breaking: bool,
+    #[serde(skip_serializing_if = "Json::is_null")]
+    pub detail: Json,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SchemaDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+
+    fn push(&mut self, path: &str, kind: ChangeKind, breaking: bool, detail: Json) {
+        self.changes.push(Change {
+            path: path.into(),
+            kind,
+            breaking,
+            detail,
+        });
+    }
+}
+
+pub fn diff(old: &dyn OkSchema, new: &dyn OkSchema) -> Result<SchemaDiff, SpecError> {
+    let mut diff = SchemaDiff::default();
+    compare("", &old.to_spec()?, &new.to_spec()?, &mut diff);
+    Ok(diff)
+}
+
+fn compare(path: &str, old: &SchemaSpec, new: &SchemaSpec, diff: &mut SchemaDiff) {
+    if old.kind != new.kind {
+        let detail = json!({ "from": old.kind, "to": new.kind });
+        return diff.push(path, ChangeKind::TypeChanged, true, detail);
+    }
+    match (old.optional, new.optional) {
+        (false, true) => diff.push(path, ChangeKind::MadeOptional, false, Json::Null),
+        (true, false) => diff.push(path, ChangeKind::MadeRequired, true, Json::Null),
+        _ => (),
+    }
+    match (old.nullable, new.nullable) {
+        (false, true) => diff.push(path, ChangeKind::MadeNullable, false, Json::Null),
+        (true, false) => diff.push(path, ChangeKind::MadeNonNullable, true, Json::Null),
+        _ => (),
+    }
+    if old.transforms != new.transforms {
+        let names = |rules: &[RuleSpec]| {
+            rules
+                .iter()
+                .map(|rule| rule.name.clone())
+                .collect::<Vec<String>>()
+        };
+        let detail = json!({ "from": names(&old.transforms), "to": names(&new.transforms) });
+        diff.push(path, ChangeKind::TransformsChanged, true, detail);
+    }
+    compare_tests(path, &old.tests, &new.tests, diff);
+    if let (Some(old), Some(new)) = (&old.elements, &new.elements) {
+        compare(&format!("{}[]", path), old, new, diff);
+    }
+    let keys = old
+        .properties
+        .keys()
+        .chain(new.properties.keys())
+        .collect::<BTreeSet<_>>();
+    keys.into_iter().for_each(|key| {
+        let path = match path {
+            "" => key.to_string(),
+            path => format!("{}.{}", path, key),
+        };
+        match (old.properties.get(key), new.properties.get(key)) {
+            (Some(old), Some(new)) => compare(&path, old, new, diff),
+            (None, Some(new)) => {
+                let detail = json!({ "required": !new.optional });
+                diff.push(&path, ChangeKind::KeyAdded, !new.optional, detail);
+            }
+            (Some(_), None) => diff.push(&path, ChangeKind::KeyRemoved, true, Json::Null),
+            (None, None) => (),
+        }
+    });
+}
+
+fn compare_tests(path: &str, old: &[RuleSpec], new: &[RuleSpec], diff: &mut SchemaDiff) {
+    old.iter().for_each(|old_rule| {
+        match new.iter().find(|new_rule| new_rule.name == old_rule.name) {
+            Some(new_rule) => compare_rule(path, old_rule, new_rule, diff),
+            None => {
+                let detail = json!({ "rule": old_rule.name, "params": old_rule.params });
+                diff.push(path, ChangeKind::RuleRemoved, false, detail);
+            }
+        }
+    });
+    new.iter()
+        .filter(|new_rule| !old.iter().any(|old_rule| old_rule.name == new_rule.name))
+        .for_each(|new_rule| {
+            let detail = json!({ "rule": new_rule.name, "params": new_rule.params });
+            diff.push(path, ChangeKind::RuleAdded, true, detail);
+        });
+}
+
+fn compare_rule(path: &str, old: &RuleSpec, new: &RuleSpec, diff: &mut SchemaDiff) {
+    if old.params == new.params {
+        return;
+    }
+    let bounds: &[(&str, bool)] = match old.name.as_str() {
+        "min" | "min_length" | "min_size" => &[("min", true)],
+        "max" | "max_length" | "max_size" => &[("max", false)],
+        "greater_than" => &[("limit", true)],
+        "less_than" => &[("limit", false)],
+        "length" => &[("min", true), ("max", false)],
+        "one_of" => return compare_values(path, old, new, false, diff),
+        "not_one_of" => return compare_values(path, old, new, true, diff),
+        _ => &[],
+    };
+    let changed = bounds.iter().filter_map(|&(param, is_lower)| {
+        let from = old.params.get(param).and_then(Json::as_f64)?;
+        let to = new.params.get(param).and_then(Json::as_f64)?;
+        match is_lower {
+            true if to < from => Some((param, false)),
+            false if to > from => Some((param, false)),
+            _ if to == from => None,
+            _ => Some((param, true)),
+        }
+    });
+    let mut compared = false;
+    changed.for_each(|(param, narrowed)| {
+        compared = true;
+        let detail = json!({
+            "rule": old.name,
+            "param": param,
+            "from": old.params[param],
+            "to": new.params[param],
+        });
+        match narrowed {
+            true => diff.push(path, ChangeKind::BoundNarrowed, true, detail),
+            false => diff.push(path, ChangeKind::BoundWidened, false, detail),
+        }
+    });
+    if !compared {
+        let detail = json!({ "rule": old.name, "from": old.params, "to": new.params });
+        diff.push(path, ChangeKind::RuleChanged, true, detail);
+    }
+}
+
+fn compare_values(path: &str, old: &RuleSpec, new: &RuleSpec, denied: bool, diff: &mut SchemaDiff) {
+    let values = |rule: &RuleSpec| match rule.params.get("values") {
+        Some(Json::Array(values)) => values.clone(),
+        _ => vec![],
+    };
+    let (old_values, new_values) = (values(old), values(new));
+    let added = new_values
+        .iter()
+        .filter(|value| !old_values.contains(value))
+        .cloned()
+        .collect::<Vec<Json>>();
+    let removed = old_values
+        .iter()
+        .filter(|value| !new_values.contains(value))
+        .cloned()
+        .collect::<Vec<Json>>();
+    if !added.is_empty() {
+        let detail = json!({ "rule": old.name, "values": added });
+        diff.push(path, ChangeKind::ValuesAdded, denied, detail);
+    }
+    if !removed.is_empty() {
+        let detail = json!({ "rule": old.name, "values": removed });
+        diff.push(path, ChangeKind::ValuesRemoved, !denied, detail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        array, diff, integer, object, string, Change, ChangeKind, OkSchema, SchemaRegistry,
+    };
+    use serde_json::json;
+
+    fn v1() -> impl OkSchema {
+        object()
+            .string("name", |name| name.min_length(1).max_length(20))
+            .integer("age", |age| age.min(18).not_one_of(vec![13]))
+            .string("nickname", |nickname| nickname)
+            .object("address", |address| {
+                address
+                    .string("city", |city| city.optional())
+                    .string("zip", |zip| zip)
+            })
+            .array("tags", |tags| tags.of(string().max_length(8)))
+    }
+
+    fn v2() -> impl OkSchema {
+        object()
+            .string("name", |name| name.min_length(1).max_length(40))
+            .integer("age", |age| age.min(21).not_one_of(vec![13, 14]))
+            .string("email", |email| email)
+            .string("referrer", |referrer| referrer.optional())
+            .object("address", |address| {
+                address
+                    .string("city", |city| city)
+                    .integer("zip", |zip| zip)
+            })
+            .array("tags", |tags| tags.of(string().max_length(8).trim()))
+    }
+
+    #[test]
+    fn it_classifies_changes() {
+        let diff = diff(&v1(), &v2()).unwrap();
+        let kinds = diff
+            .changes
+            .iter()
+            .map(|change| (change.path.as_str(), change.kind, change.breaking))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("address.city", ChangeKind::MadeRequired, true),
+                ("address.zip", ChangeKind::TypeChanged, true),
+                ("age", ChangeKind::BoundNarrowed, true),
+                ("age", ChangeKind::ValuesAdded, true),
+                ("email", ChangeKind::KeyAdded, true),
+                ("name", ChangeKind::BoundWidened, false),
+                ("nickname", ChangeKind::KeyRemoved, true),
+                ("referrer", ChangeKind::KeyAdded, false),
+                ("tags[]", ChangeKind::TransformsChanged, true),
+            ]
+        );
+        assert!(diff.has_breaking_changes());
+        assert_eq!(
+            diff.changes[2],
+            Change {
+                path: "age".into(),
+                kind: ChangeKind::BoundNarrowed,
+                breaking: true,
+                detail: json!({ "rule": "min", "param": "min", "from": 18, "to": 21 })
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&diff.changes[5]).unwrap(),
+            json!({
+                "path": "name",
+                "kind": "bound_widened",
+                "breaking": false,
+                "detail": { "rule": "max_length", "param": "max", "from": 20, "to": 40 }
+            })
+        );
+    }
+
+    #[test]
+    fn it_reports_compatible_changes() {
+        let old = object().integer("age", |age| age.min(18).max(99));
+        let new = object()
+            .integer("age", |age| age.max(120).nullable())
+            .string("bio", |bio| bio.optional());
+        let diff = diff(&old, &new).unwrap();
+        assert!(!diff.has_breaking_changes());
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|change| change.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                ChangeKind::MadeNullable,
+                ChangeKind::RuleRemoved,
+                ChangeKind::BoundWidened,
+                ChangeKind::KeyAdded,
+            ]
+        );
+        assert!(super::diff(&array(), &array()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_requires_serializable_schemas() {
+        let registry = SchemaRegistry::new();
+        let old = object().key("user", registry.reference("User"));
+        assert!(diff(&old, &integer()).is_err());
+    }
+}
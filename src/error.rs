@@ -1,12 +1,343 @@
-use super::json::JsonType;
-use serde::Serialize;
+use super::json::{preview, Json, JsonType, Object};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::json;
+use std::{borrow::Cow, collections::BTreeMap, time::Duration};
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct ValidationError {
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorCode {
+    Alphanumeric,
+    Ascii,
+    Base64,
+    Between,
+    Contains,
+    CountryCode,
+    CreditCard,
+    CurrencyCode,
+    Date,
+    Datetime,
+    DecimalPlaces,
+    Digits,
+    DoesNotMatch,
+    DuplicateKey,
+    Email,
+    EndsWith,
+    Even,
+    Finite,
+    GreaterThan,
+    Hex,
+    Hostname,
+    Internal,
+    InvalidBase64,
+    InvalidByte,
+    InvalidJson,
+    Ip,
+    Ipv4,
+    Ipv6,
+    IsFalse,
+    IsLowercase,
+    IsTrue,
+    IsUppercase,
+    JsonString,
+    Latitude,
+    Length,
+    LessThan,
+    Longitude,
+    Matches,
+    MatchesAny,
+    Max,
+    MaxBytes,
+    MaxLength,
+    MaxSignificantDigits,
+    MaxSize,
+    MigrationFailed,
+    MimeType,
+    MimeTypeIn,
+    Min,
+    MinLength,
+    MinSize,
+    MissingMigration,
+    Negative,
+    NoControlChars,
+    NonBlank,
+    NonEmpty,
+    NonNegative,
+    NonZero,
+    NotOneOf,
+    Odd,
+    OneOf,
+    OutOfRange,
+    PasswordDigit,
+    PasswordLength,
+    PasswordLower,
+    PasswordSymbol,
+    PasswordUpper,
+    Percent,
+    Port,
+    Positive,
+    Precision,
+    Printable,
+    SafeInteger,
+    Semver,
+    Slug,
+    StartsWith,
+    Time,
+    Timeout,
+    TypeError,
+    Unique,
+    UniqueBy,
+    UnresolvedReference,
+    Url,
+    Uuid,
+    Custom(Cow<'static, str>),
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::Alphanumeric => "alphanumeric",
+            ErrorCode::Ascii => "ascii",
+            ErrorCode::Base64 => "base64",
+            ErrorCode::Between => "between",
+            ErrorCode::Contains => "contains",
+            ErrorCode::CountryCode => "country_code",
+            ErrorCode::CreditCard => "credit_card",
+            ErrorCode::CurrencyCode => "currency_code",
+            ErrorCode::Date => "date",
+            ErrorCode::Datetime => "datetime",
+            ErrorCode::DecimalPlaces => "decimal_places",
+            ErrorCode::Digits => "digits",
+            ErrorCode::DoesNotMatch => "does_not_match",
+            ErrorCode::DuplicateKey => "duplicate_key",
+            ErrorCode::Email => "email",
+            ErrorCode::EndsWith => "ends_with",
+            ErrorCode::Even => "even",
+            ErrorCode::Finite => "finite",
+            ErrorCode::GreaterThan => "greater_than",
+            ErrorCode::Hex => "hex",
+            ErrorCode::Hostname => "hostname",
+            ErrorCode::Internal => "internal",
+            ErrorCode::InvalidBase64 => "invalid_base64",
+            ErrorCode::InvalidByte => "invalid_byte",
+            ErrorCode::InvalidJson => "invalid_json",
+            ErrorCode::Ip => "ip",
+            ErrorCode::Ipv4 => "ipv4",
+            ErrorCode::Ipv6 => "ipv6",
+            ErrorCode::IsFalse => "is_false",
+            ErrorCode::IsLowercase => "is_lowercase",
+            ErrorCode::IsTrue => "is_true",
+            ErrorCode::IsUppercase => "is_uppercase",
+            ErrorCode::JsonString => "json_string",
+            ErrorCode::Latitude => "latitude",
+            ErrorCode::Length => "length",
+            ErrorCode::LessThan => "less_than",
+            ErrorCode::Longitude => "longitude",
+            ErrorCode::Matches => "matches",
+            ErrorCode::MatchesAny => "matches_any",
+            ErrorCode::Max => "max",
+            ErrorCode::MaxBytes => "max_bytes",
+            ErrorCode::MaxLength => "max_length",
+            ErrorCode::MaxSignificantDigits => "max_significant_digits",
+            ErrorCode::MaxSize => "max_size",
+            ErrorCode::MigrationFailed => "migration_failed",
+            ErrorCode::MimeType => "mime_type",
+            ErrorCode::MimeTypeIn => "mime_type_in",
+            ErrorCode::Min => "min",
+            ErrorCode::MinLength => "min_length",
+            ErrorCode::MinSize => "min_size",
+            ErrorCode::MissingMigration => "missing_migration",
+            ErrorCode::Negative => "negative",
+            ErrorCode::NoControlChars => "no_control_chars",
+            ErrorCode::NonBlank => "non_blank",
+            ErrorCode::NonEmpty => "non_empty",
+            ErrorCode::NonNegative => "non_negative",
+            ErrorCode::NonZero => "non_zero",
+            ErrorCode::NotOneOf => "not_one_of",
+            ErrorCode::Odd => "odd",
+            ErrorCode::OneOf => "one_of",
+            ErrorCode::OutOfRange => "out_of_range",
+            ErrorCode::PasswordDigit => "password_digit",
+            ErrorCode::PasswordLength => "password_length",
+            ErrorCode::PasswordLower => "password_lower",
+            ErrorCode::PasswordSymbol => "password_symbol",
+            ErrorCode::PasswordUpper => "password_upper",
+            ErrorCode::Percent => "percent",
+            ErrorCode::Port => "port",
+            ErrorCode::Positive => "positive",
+            ErrorCode::Precision => "precision",
+            ErrorCode::Printable => "printable",
+            ErrorCode::SafeInteger => "safe_integer",
+            ErrorCode::Semver => "semver",
+            ErrorCode::Slug => "slug",
+            ErrorCode::StartsWith => "starts_with",
+            ErrorCode::Time => "time",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::TypeError => "type_error",
+            ErrorCode::Unique => "unique",
+            ErrorCode::UniqueBy => "unique_by",
+            ErrorCode::UnresolvedReference => "unresolved_reference",
+            ErrorCode::Url => "url",
+            ErrorCode::Uuid => "uuid",
+            ErrorCode::Custom(code) => code,
+        }
+    }
+}
+
+impl From<Cow<'static, str>> for ErrorCode {
+    fn from(code: Cow<'static, str>) -> Self {
+        match code.as_ref() {
+            "alphanumeric" => ErrorCode::Alphanumeric,
+            "ascii" => ErrorCode::Ascii,
+            "base64" => ErrorCode::Base64,
+            "between" => ErrorCode::Between,
+            "contains" => ErrorCode::Contains,
+            "country_code" => ErrorCode::CountryCode,
+            "credit_card" => ErrorCode::CreditCard,
+            "currency_code" => ErrorCode::CurrencyCode,
+            "date" => ErrorCode::Date,
+            "datetime" => ErrorCode::Datetime,
+            "decimal_places" => ErrorCode::DecimalPlaces,
+            "digits" => ErrorCode::Digits,
+            "does_not_match" => ErrorCode::DoesNotMatch,
+            "duplicate_key" => ErrorCode::DuplicateKey,
+            "email" => ErrorCode::Email,
+            "ends_with" => ErrorCode::EndsWith,
+            "even" => ErrorCode::Even,
+            "finite" => ErrorCode::Finite,
+            "greater_than" => ErrorCode::GreaterThan,
+            "hex" => ErrorCode::Hex,
+            "hostname" => ErrorCode::Hostname,
+            "internal" => ErrorCode::Internal,
+            "invalid_base64" => ErrorCode::InvalidBase64,
+            "invalid_byte" => ErrorCode::InvalidByte,
+            "invalid_json" => ErrorCode::InvalidJson,
+            "ip" => ErrorCode::Ip,
+            "ipv4" => ErrorCode::Ipv4,
+            "ipv6" => ErrorCode::Ipv6,
+            "is_false" => ErrorCode::IsFalse,
+            "is_lowercase" => ErrorCode::IsLowercase,
+            "is_true" => ErrorCode::IsTrue,
+            "is_uppercase" => ErrorCode::IsUppercase,
+            "json_string" => ErrorCode::JsonString,
+            "latitude" => ErrorCode::Latitude,
+            "length" => ErrorCode::Length,
+            "less_than" => ErrorCode::LessThan,
+            "longitude" => ErrorCode::Longitude,
+            "matches" => ErrorCode::Matches,
+            "matches_any" => ErrorCode::MatchesAny,
+            "max" => ErrorCode::Max,
+            "max_bytes" => ErrorCode::MaxBytes,
+            "max_length" => ErrorCode::MaxLength,
+            "max_significant_digits" => ErrorCode::MaxSignificantDigits,
+            "max_size" => ErrorCode::MaxSize,
+            "migration_failed" => ErrorCode::MigrationFailed,
+            "mime_type" => ErrorCode::MimeType,
+            "mime_type_in" => ErrorCode::MimeTypeIn,
+            "min" => ErrorCode::Min,
+            "min_length" => ErrorCode::MinLength,
+            "min_size" => ErrorCode::MinSize,
+            "missing_migration" => ErrorCode::MissingMigration,
+            "negative" => ErrorCode::Negative,
+            "no_control_chars" => ErrorCode::NoControlChars,
+            "non_blank" => ErrorCode::NonBlank,
+            "non_empty" => ErrorCode::NonEmpty,
+            "non_negative" => ErrorCode::NonNegative,
+            "non_zero" => ErrorCode::NonZero,
+            "not_one_of" => ErrorCode::NotOneOf,
+            "odd" => ErrorCode::Odd,
+            "one_of" => ErrorCode::OneOf,
+            "out_of_range" => ErrorCode::OutOfRange,
+            "password_digit" => ErrorCode::PasswordDigit,
+            "password_length" => ErrorCode::PasswordLength,
+            "password_lower" => ErrorCode::PasswordLower,
+            "password_symbol" => ErrorCode::PasswordSymbol,
+            "password_upper" => ErrorCode::PasswordUpper,
+            "percent" => ErrorCode::Percent,
+            "port" => ErrorCode::Port,
+            "positive" => ErrorCode::Positive,
+            "precision" => ErrorCode::Precision,
+            "printable" => ErrorCode::Printable,
+            "safe_integer" => ErrorCode::SafeInteger,
+            "semver" => ErrorCode::Semver,
+            "slug" => ErrorCode::Slug,
+            "starts_with" => ErrorCode::StartsWith,
+            "time" => ErrorCode::Time,
+            "timeout" => ErrorCode::Timeout,
+            "type_error" => ErrorCode::TypeError,
+            "unique" => ErrorCode::Unique,
+            "unique_by" => ErrorCode::UniqueBy,
+            "unresolved_reference" => ErrorCode::UnresolvedReference,
+            "url" => ErrorCode::Url,
+            "uuid" => ErrorCode::Uuid,
+            _ => ErrorCode::Custom(code),
+        }
+    }
+}
+
+impl From<&'static str> for ErrorCode {
+    fn from(code: &'static str) -> Self {
+        ErrorCode::from(Cow::Borrowed(code))
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        ErrorCode::from(Cow::Owned(code))
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(ErrorCode::from)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationError(Box<ErrorDetails>);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ErrorDetails {
     path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    segments: Vec<PathSegment>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
     message: String,
     #[serde(rename = "type")]
-    type_: &'static str,
+    code: ErrorCode,
+    #[serde(default, skip_serializing_if = "Object::is_empty")]
+    params: Object,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expected: Option<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual: Option<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    #[serde(skip)]
+    redacted_message: Option<String>,
     errors: Vec<ValidationError>,
 }
 
@@ -18,53 +349,574 @@ pub fn type_error<L: std::fmt::Display>(
     path: &str,
     label: L,
     json_type: JsonType,
+    received: Option<&Json>,
 ) -> ValidationError {
-    ValidationError {
-        path: path.into(),
-        type_: "type_error",
-        message: format!("{} must be of type `{}`.", label, json_type),
-        errors: vec![],
-    }
+    let preview = received.map_or_else(|| "nothing".into(), preview);
+    let received = received.map_or(JsonType::None, JsonType::from);
+    ValidationError::new(
+        ErrorCode::TypeError,
+        format!(
+            "{} must be of type `{}`, but received {}.",
+            label, json_type, preview
+        ),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({
+        "expected": json_type.as_str(),
+        "received": received.as_str(),
+        "preview": preview,
+    }))
+}
+
+pub fn range_error<L: std::fmt::Display>(
+    path: &str,
+    label: L,
+    json_type: JsonType,
+    received: &Json,
+) -> ValidationError {
+    let preview = preview(received);
+    ValidationError::new(
+        ErrorCode::OutOfRange,
+        format!(
+            "{} is out of range for type `{}`, but received {}.",
+            label, json_type, preview
+        ),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({
+        "expected": json_type.as_str(),
+        "received": JsonType::from(received).as_str(),
+        "preview": preview,
+    }))
+}
+
+pub fn precision_error<L: std::fmt::Display>(
+    path: &str,
+    label: L,
+    json_type: JsonType,
+    received: &Json,
+) -> ValidationError {
+    let preview = preview(received);
+    ValidationError::new(
+        ErrorCode::Precision,
+        format!(
+            "{} cannot be represented exactly as type `{}`, but received {}.",
+            label, json_type, preview
+        ),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({
+        "expected": json_type.as_str(),
+        "received": JsonType::from(received).as_str(),
+        "preview": preview,
+    }))
+}
+
+pub fn epoch_error<L: std::fmt::Display>(
+    path: &str,
+    label: L,
+    received: &Json,
+    (min, max): (&str, &str),
+) -> ValidationError {
+    let preview = preview(received);
+    ValidationError::new(
+        ErrorCode::OutOfRange,
+        format!(
+            "{} must be an epoch timestamp between {} and {}, but received {}.",
+            label, min, max, preview
+        ),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({
+        "min": min,
+        "max": max,
+        "received": JsonType::from(received).as_str(),
+        "preview": preview,
+    }))
+}
+
+pub fn base64_error<L: std::fmt::Display>(
+    path: &str,
+    label: L,
+    received: &Json,
+) -> ValidationError {
+    let preview = preview(received);
+    ValidationError::new(
+        ErrorCode::InvalidBase64,
+        format!("{} must be valid base64, but received {}.", label, preview),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({ "preview": preview }))
+}
+
+pub fn byte_error<L: std::fmt::Display>(path: &str, label: L, received: &Json) -> ValidationError {
+    let preview = preview(received);
+    ValidationError::new(
+        ErrorCode::InvalidByte,
+        format!(
+            "{} must be an integer between 0 and 255, but received {}.",
+            label, preview
+        ),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({
+        "received": JsonType::from(received).as_str(),
+        "preview": preview,
+    }))
+}
+
+pub fn duplicate_key_error(segments: Vec<PathSegment>, key: &str) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::DuplicateKey,
+        format!(
+            "{} must not appear more than once.",
+            segment_label(&segments)
+        ),
+    )
+    .with_path_segments(segments)
+    .with_params(json!({ "key": key }))
+}
+
+pub fn missing_migration_error(path: &str, from: u64, to: u64) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::MissingMigration,
+        format!(
+            "No migration is registered from version {} to {}.",
+            from, to
+        ),
+    )
+    .with_path(path)
+    .with_params(json!({ "from": from, "to": to }))
+}
+
+pub fn migration_error(path: &str, from: u64, to: u64, reason: &str) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::MigrationFailed,
+        format!(
+            "Migration from version {} to {} failed: {}",
+            from, to, reason
+        ),
+    )
+    .with_path(path)
+    .with_params(json!({ "from": from, "to": to, "reason": reason }))
+}
+
+pub fn timeout_error(path: &str, budget: Duration, processed: usize) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::Timeout,
+        format!(
+            "Validation exceeded its time budget of {}ms after processing {} values.",
+            budget.as_millis(),
+            processed
+        ),
+    )
+    .with_path(path)
+    .with_params(json!({ "budget_ms": budget.as_millis() as u64, "processed": processed }))
+}
+
+pub fn internal_error<D: std::fmt::Display>(path: &str, detail: D) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::Internal,
+        format!("An internal error occurred: {}.", detail),
+    )
+    .with_path(path)
+}
+
+pub fn syntax_error(message: String) -> ValidationError {
+    ValidationError::new(ErrorCode::InvalidJson, message)
 }
 
 pub fn test_error<S: Into<String>>(type_: &'static str, path: S, message: S) -> ValidationError {
-    ValidationError {
-        type_,
-        path: path.into(),
-        message: message.into(),
-        errors: vec![],
-    }
+    ValidationError::new(type_, message).with_path(path)
+}
+
+pub fn reference_error<L: std::fmt::Display>(path: &str, label: L, name: &str) -> ValidationError {
+    ValidationError::new(
+        ErrorCode::UnresolvedReference,
+        format!("{} references an unregistered schema `{}`.", label, name),
+    )
+    .with_path(path)
+    .with_label(field_label(path, &label.to_string()))
+    .with_params(json!({ "name": name }))
 }
 
 pub fn json_error(all_errors: Vec<ValidationError>) -> ValidationError {
-    let error_count = all_errors.len();
+    let error_count = all_errors
+        .iter()
+        .map(ValidationError::leaf_count)
+        .sum::<usize>();
     let pluralized = if error_count == 1 { "error" } else { "errors" };
     let message = format!("{} validation {} occurred.", error_count, pluralized);
-    ValidationError {
-        message,
-        path: "".into(),
-        errors: all_errors,
-        type_: "invalid_json",
+    ValidationError::new(ErrorCode::InvalidJson, message).with_children(all_errors)
+}
+
+pub const REDACTED: &str = "[redacted]";
+
+pub fn field_label(path: &str, label: &str) -> String {
+    match label == path {
+        true => segment_label(&parse_segments(path)),
+        false => label.into(),
+    }
+}
+
+/// The last key in `segments`, followed by any indices after it.
+pub fn segment_label(segments: &[PathSegment]) -> String {
+    let start = segments
+        .iter()
+        .rposition(|segment| matches!(segment, PathSegment::Key(_)))
+        .unwrap_or(0);
+    dotted_path(&segments[start..])
+}
+
+pub struct ProblemOptions {
+    type_uri: String,
+    title: String,
+}
+
+impl ProblemOptions {
+    pub fn new() -> Self {
+        ProblemOptions {
+            type_uri: "about:blank".into(),
+            title: "Unprocessable Entity".into(),
+        }
+    }
+
+    pub fn type_uri<S: Into<String>>(mut self, type_uri: S) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
     }
 }
 
+impl Default for ProblemOptions {
+    fn default() -> Self {
+        ProblemOptions::new()
+    }
+}
+
+impl ValidationError {
+    pub fn new<C: Into<ErrorCode>, M: Into<String>>(code: C, message: M) -> Self {
+        ValidationError(Box::new(ErrorDetails {
+            path: "".into(),
+            segments: vec![],
+            label: "".into(),
+            description: None,
+            line: None,
+            column: None,
+            message: message.into(),
+            code: code.into(),
+            params: Object::new(),
+            expected: None,
+            actual: None,
+            redacted_message: None,
+            errors: vec![],
+        }))
+    }
+
+    pub fn with_path<P: Into<String>>(mut self, path: P) -> Self {
+        self.0.path = path.into();
+        self.0.segments = parse_segments(&self.0.path);
+        if self.0.label.is_empty() {
+            self.0.label = field_label(&self.0.path, &self.0.path);
+        }
+        self
+    }
+
+    pub fn with_children(mut self, errors: Vec<ValidationError>) -> Self {
+        self.0.errors = errors;
+        self
+    }
+
+    pub fn code(&self) -> &ErrorCode {
+        &self.0.code
+    }
+
+    pub fn path(&self) -> &str {
+        &self.0.path
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0.segments
+    }
+
+    pub fn with_segments(mut self, segments: Vec<PathSegment>) -> Self {
+        self.0.segments = segments;
+        self
+    }
+
+    /// Like `with_path`, but keeps keys that contain `.` or `[` intact.
+    pub fn with_path_segments(mut self, segments: Vec<PathSegment>) -> Self {
+        self.0.path = dotted_path(&segments);
+        if self.0.label.is_empty() {
+            self.0.label = segment_label(&segments);
+        }
+        self.0.segments = segments;
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    pub fn label(&self) -> &str {
+        &self.0.label
+    }
+
+    pub fn with_label<L: Into<String>>(mut self, label: L) -> Self {
+        self.0.label = label.into();
+        self
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.0.description = Some(description.into());
+        self
+    }
+
+    pub fn params(&self) -> &Object {
+        &self.0.params
+    }
+
+    pub fn with_params(mut self, params: Json) -> Self {
+        if let Json::Object(params) = params {
+            self.0.params.extend(params);
+        }
+        self
+    }
+
+    pub fn expected(&self) -> Option<&Json> {
+        self.0.expected.as_ref()
+    }
+
+    pub fn actual(&self) -> Option<&Json> {
+        self.0.actual.as_ref()
+    }
+
+    pub fn with_measure(mut self, expected: Json, actual: Json) -> Self {
+        self = self.with_params(expected.clone());
+        self.0.params.insert("actual".into(), actual.clone());
+        self.0.expected = Some(expected).filter(|expected| !expected.is_null());
+        self.0.actual = Some(actual);
+        self
+    }
+
+    pub fn flatten(&self) -> impl Iterator<Item = &ValidationError> {
+        let mut leaves = vec![];
+        self.collect_leaves(&mut leaves);
+        leaves.into_iter()
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        match self.0.errors.is_empty() {
+            true => 1,
+            false => self.0.errors.iter().map(ValidationError::leaf_count).sum(),
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.0.line
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.0.column
+    }
+
+    pub fn with_location(mut self, line: usize, column: usize) -> Self {
+        self.0.line = Some(line);
+        self.0.column = Some(column);
+        self
+    }
+
+    pub fn with_locations(mut self, locations: &BTreeMap<String, (usize, usize)>) -> Self {
+        if self.0.errors.is_empty() && self.0.line.is_none() {
+            if let Some(&(line, column)) = locations.get(&json_pointer(&self.0.segments)) {
+                return self.with_location(line, column);
+            }
+        }
+        self.0.errors = std::mem::take(&mut self.0.errors)
+            .into_iter()
+            .map(|error| error.with_locations(locations))
+            .collect();
+        self
+    }
+
+    pub fn with_json_pointer_paths(mut self) -> Self {
+        self.0.path = json_pointer(&self.0.segments);
+        self.0.errors = std::mem::take(&mut self.0.errors)
+            .into_iter()
+            .map(ValidationError::with_json_pointer_paths)
+            .collect();
+        self
+    }
+
+    pub fn to_problem_details(&self, base: ProblemOptions) -> Json {
+        let errors = self
+            .flatten()
+            .map(|error| {
+                json!({
+                    "pointer": json_pointer(&error.0.segments),
+                    "message": error.0.message,
+                    "code": error.0.code.as_str(),
+                })
+            })
+            .collect::<Vec<Json>>();
+        json!({
+            "type": base.type_uri,
+            "title": base.title,
+            "status": 422,
+            "detail": self.0.message,
+            "errors": errors,
+        })
+    }
+
+    /// Replaces the message when redacted, for messages that may embed the value.
+    pub fn with_redacted_message<M: Into<String>>(mut self, message: M) -> Self {
+        self.0.redacted_message = Some(message.into());
+        self
+    }
+
+    pub fn redacted(mut self) -> Self {
+        if let Some(message) = self.0.redacted_message.take() {
+            self.0.message = message;
+        }
+        if let Some(Json::String(preview)) = self.0.params.get("preview") {
+            self.0.message = self.0.message.replace(preview.as_str(), REDACTED);
+        }
+        ["preview", "actual"].iter().for_each(|key| {
+            if let Some(value) = self.0.params.get_mut(*key) {
+                *value = Json::from(REDACTED);
+            }
+        });
+        if let Some(actual) = self.0.actual.as_mut() {
+            *actual = Json::from(REDACTED);
+        }
+        self.0.errors = std::mem::take(&mut self.0.errors)
+            .into_iter()
+            .map(ValidationError::redacted)
+            .collect();
+        self
+    }
+
+    pub fn by_field(&self) -> BTreeMap<String, Vec<String>> {
+        self.flatten().fold(BTreeMap::new(), |mut fields, error| {
+            fields
+                .entry(error.0.path.clone())
+                .or_insert_with(Vec::new)
+                .push(error.0.message.clone());
+            fields
+        })
+    }
+
+    pub fn to_field_map(&self) -> Json {
+        json!(self.by_field())
+    }
+
+    pub fn to_report(&self) -> String {
+        let mut report = String::new();
+        self.write_report(&mut report, 0);
+        report
+    }
+
+    fn write_report(&self, report: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        match self.0.path.as_str() {
+            "" => report.push_str(&format!("{}✗ {}\n", indent, self.0.message)),
+            path => report.push_str(&format!("{}✗ {} — {}\n", indent, path, self.0.message)),
+        }
+        self.0
+            .errors
+            .iter()
+            .for_each(|error| error.write_report(report, depth + 1));
+    }
+
+    fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a ValidationError>) {
+        if self.0.errors.is_empty() {
+            return leaves.push(self);
+        }
+        self.0
+            .errors
+            .iter()
+            .for_each(|error| error.collect_leaves(leaves));
+    }
+}
+
+pub fn parse_segments(path: &str) -> Vec<PathSegment> {
+    path.split(['.', '['])
+        .filter(|segment| !segment.is_empty())
+        .map(
+            |segment| match segment.strip_suffix(']').map(str::parse::<usize>) {
+                Some(Ok(index)) => PathSegment::Index(index),
+                _ => PathSegment::Key(segment.into()),
+            },
+        )
+        .collect()
+}
+
+pub fn dotted_path(segments: &[PathSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(position, segment)| match (position, segment) {
+            (0, PathSegment::Key(key)) => key.clone(),
+            (_, PathSegment::Key(key)) => format!(".{}", key),
+            (_, PathSegment::Index(index)) => format!("[{}]", index),
+        })
+        .collect()
+}
+
+pub fn json_pointer(segments: &[PathSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Index(index) => format!("/{}", index),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::{
-        error::{json_error, test_error, type_error},
-        json::{to_json, JsonType},
+        error::{
+            dotted_path, json_error, json_pointer, parse_segments, test_error, type_error,
+            ErrorCode, PathSegment, ProblemOptions, ValidationError,
+        },
+        json::{to_json, Json, JsonType},
+        object,
+        test::{Test, TestOutcome},
+        OkSchema,
     };
     use serde_json::json;
 
     #[test]
     fn it_serializes_type_errors() {
-        let err = type_error("foo", "My Boolean", JsonType::Boolean);
+        let err = type_error("foo", "My Boolean", JsonType::Boolean, Some(&json!("bar")));
         assert_eq!(
             to_json(err).unwrap(),
             json!({
                 "type": "type_error",
                 "path": "foo",
-                "message": "My Boolean must be of type `Boolean`.",
+                "segments": ["foo"],
+                "label": "My Boolean",
+                "message": "My Boolean must be of type `Boolean`, but received String(\"bar\").",
+                "params": {
+                    "expected": "Boolean",
+                    "received": "String",
+                    "preview": "String(\"bar\")"
+                },
                 "errors": []
             })
         );
@@ -78,15 +930,140 @@ mod tests {
             json!({
                 "type": "no_good",
                 "path": "foo",
+                "segments": ["foo"],
+                "label": "foo",
                 "message": "Validation failed for foo!",
                 "errors": []
             })
         );
     }
 
+    #[test]
+    fn it_serializes_custom_test_error_params() {
+        let err = test_error("no_good", "foo", "Validation failed for foo!")
+            .with_params(json!({ "reason": "too good" }));
+        assert_eq!(err.params().get("reason"), Some(&json!("too good")));
+        assert_eq!(
+            to_json(err).unwrap(),
+            json!({
+                "type": "no_good",
+                "path": "foo",
+                "segments": ["foo"],
+                "label": "foo",
+                "message": "Validation failed for foo!",
+                "params": { "reason": "too good" },
+                "errors": []
+            })
+        );
+    }
+
+    #[test]
+    fn it_serializes_expected_and_actual_values() {
+        let mut test = Test::new(
+            "min",
+            "<label> must be at least 5.",
+            json!({ "min": 5 }),
+            |n: &i64| Ok(*n >= 5),
+        );
+        test.measure(|n| json!(n));
+        let err = test.check("count", "Count", &3).unwrap_err();
+        assert_eq!(err.expected(), Some(&json!({ "min": 5 })));
+        assert_eq!(err.actual(), Some(&json!(3)));
+        assert_eq!(
+            to_json(err).unwrap(),
+            json!({
+                "type": "min",
+                "path": "count",
+                "segments": ["count"],
+                "label": "Count",
+                "message": "Count must be at least 5.",
+                "params": { "min": 5, "actual": 3 },
+                "expected": { "min": 5 },
+                "actual": 3,
+                "errors": []
+            })
+        );
+        let err = test_error("positive", "n", "n must be positive.")
+            .with_measure(Json::Null, json!(-1))
+            .redacted();
+        assert_eq!(err.expected(), None);
+        assert_eq!(err.actual(), Some(&json!("[redacted]")));
+    }
+
+    #[test]
+    fn it_exposes_error_codes() {
+        let err = json_error(vec![
+            type_error("foo", "foo", JsonType::String, None),
+            test_error(
+                "min_length",
+                "bar",
+                "bar must be at least 3 characters long.",
+            ),
+            test_error("not_one_of", "baz", "baz must not be one of 4, 13."),
+            test_error("no_good", "qux", "Validation failed for qux!"),
+        ]);
+        assert_eq!(err.code(), &ErrorCode::InvalidJson);
+        assert_eq!(
+            err.flatten().map(ValidationError::code).collect::<Vec<_>>(),
+            vec![
+                &ErrorCode::TypeError,
+                &ErrorCode::MinLength,
+                &ErrorCode::NotOneOf,
+                &ErrorCode::Custom("no_good".into()),
+            ]
+        );
+        assert!(matches!(
+            err.flatten().last().map(ValidationError::code),
+            Some(ErrorCode::Custom(code)) if code == "no_good"
+        ));
+    }
+
+    #[test]
+    fn it_uses_dedicated_codes_for_built_in_rules() {
+        let schema = object()
+            .string("email", |email| email.email())
+            .string("website", |website| website.url())
+            .string("plan", |plan| plan.one_of(vec!["free", "pro"]))
+            .integer("seats", |seats| seats.between(1, 10))
+            .array("tags", |tags| tags.unique());
+        let err = schema
+            .validate(json!({
+                "email": "nope",
+                "website": "nope",
+                "plan": "gold",
+                "seats": 11,
+                "tags": ["a", "a"]
+            }))
+            .unwrap_err();
+        let mut codes = err.flatten().map(ValidationError::code).collect::<Vec<_>>();
+        codes.sort_by_key(|code| code.as_str().to_string());
+        assert_eq!(
+            codes,
+            vec![
+                &ErrorCode::Between,
+                &ErrorCode::Email,
+                &ErrorCode::OneOf,
+                &ErrorCode::Unique,
+                &ErrorCode::Url,
+            ]
+        );
+        [ErrorCode::Email, ErrorCode::Ipv4, ErrorCode::UniqueBy]
+            .iter()
+            .for_each(|code| assert_eq!(&ErrorCode::from(code.to_string()), code));
+        assert_eq!(
+            to_json(ErrorCode::MimeTypeIn).unwrap(),
+            json!("mime_type_in")
+        );
+    }
+
     #[test]
     fn it_serializes_json_errors() {
-        let err = json_error(vec![type_error("foo", "My Boolean", JsonType::Boolean)]);
+        let err = json_error(vec![type_error(
+            "foo",
+            "My Boolean",
+            JsonType::Boolean,
+            None,
+        )]);
         assert_eq!(
             to_json(err).unwrap(),
             json!({
@@ -96,10 +1073,392 @@ mod tests {
                 "errors": [{
                     "type": "type_error",
                     "path": "foo",
-                    "message": "My Boolean must be of type `Boolean`.",
+                    "segments": ["foo"],
+                    "label": "My Boolean",
+                    "message": "My Boolean must be of type `Boolean`, but received nothing.",
+                    "params": {
+                        "expected": "Boolean",
+                        "received": "none",
+                        "preview": "nothing"
+                    },
                     "errors": []
                 }]
             })
         );
     }
+
+    #[test]
+    fn it_converts_paths_to_json_pointers() {
+        let json_pointer = |path| json_pointer(&parse_segments(path));
+        assert_eq!(json_pointer(""), "");
+        assert_eq!(json_pointer("foo"), "/foo");
+        assert_eq!(json_pointer("foo[0].bar"), "/foo/0/bar");
+        assert_eq!(json_pointer("[1][2]"), "/1/2");
+    }
+
+    #[test]
+    fn it_converts_errors_to_problem_details() {
+        let err = json_error(vec![
+            type_error("foo[0].bar", "bar", JsonType::String, None),
+            test_error("min", "foo[1].baz", "baz must be at least 5."),
+            test_error(
+                "max_length",
+                "qux",
+                "qux must be at most 3 characters long.",
+            ),
+        ]);
+        let options = ProblemOptions::new()
+            .type_uri("https://example.com/problems/validation")
+            .title("Your request parameters didn't validate.");
+        assert_eq!(
+            err.to_problem_details(options),
+            json!({
+                "type": "https://example.com/problems/validation",
+                "title": "Your request parameters didn't validate.",
+                "status": 422,
+                "detail": "3 validation errors occurred.",
+                "errors": [
+                    {
+                        "pointer": "/foo/0/bar",
+                        "message": "bar must be of type `String`, but received nothing.",
+                        "code": "type_error"
+                    },
+                    {
+                        "pointer": "/foo/1/baz",
+                        "message": "baz must be at least 5.",
+                        "code": "min"
+                    },
+                    {
+                        "pointer": "/qux",
+                        "message": "qux must be at most 3 characters long.",
+                        "code": "max_length"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_defaults_problem_details_options() {
+        let err = json_error(vec![type_error("", "", JsonType::Object, Some(&json!([])))]);
+        assert_eq!(
+            err.to_problem_details(ProblemOptions::default()),
+            json!({
+                "type": "about:blank",
+                "title": "Unprocessable Entity",
+                "status": 422,
+                "detail": "1 validation error occurred.",
+                "errors": [{
+                    "pointer": "",
+                    "message": " must be of type `Object`, but received Array(0 items).",
+                    "code": "type_error"
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn it_flattens_nested_errors() {
+        let err = json_error(vec![
+            type_error("foo", "foo", JsonType::Object, None),
+            json_error(vec![
+                test_error("min", "bar.baz", "bar.baz must be at least 5."),
+                test_error("max", "bar.qux", "bar.qux must be at most 3."),
+            ]),
+            test_error(
+                "min_length",
+                "quux",
+                "quux must be at least 1 characters long.",
+            ),
+        ]);
+        assert_eq!(err.leaf_count(), 4);
+        assert_eq!(
+            err.flatten()
+                .map(|error| error.path())
+                .collect::<Vec<&str>>(),
+            vec!["foo", "bar.baz", "bar.qux", "quux"]
+        );
+        assert_eq!(err.message(), "4 validation errors occurred.");
+    }
+
+    #[test]
+    fn it_flattens_a_single_error_to_itself() {
+        let err = type_error("foo", "foo", JsonType::Object, None);
+        assert_eq!(err.leaf_count(), 1);
+        assert_eq!(err.flatten().collect::<Vec<_>>(), vec![&err]);
+    }
+
+    #[test]
+    fn it_converts_error_paths_to_json_pointers() {
+        let err = json_error(vec![
+            type_error("a/b[0][1].c~d", "c~d", JsonType::Integer, Some(&json!(1.5))),
+            type_error("a/b[2]", "a/b[2]", JsonType::Array, None),
+        ]);
+        assert_eq!(
+            to_json(err.with_json_pointer_paths()).unwrap(),
+            json!({
+                "type": "invalid_json",
+                "path": "",
+                "message": "2 validation errors occurred.",
+                "errors": [
+                    {
+                        "type": "type_error",
+                        "path": "/a~1b/0/1/c~0d",
+                        "segments": ["a/b", 0, 1, "c~d"],
+                        "label": "c~d",
+                        "message": "c~d must be of type `Integer`, but received Number(1.5).",
+                        "params": {
+                            "expected": "Integer",
+                            "received": "Number",
+                            "preview": "Number(1.5)"
+                        },
+                        "errors": []
+                    },
+                    {
+                        "type": "type_error",
+                        "path": "/a~1b/2",
+                        "segments": ["a/b", 2],
+                        "label": "a/b[2]",
+                        "message": "a/b[2] must be of type `Array`, but received nothing.",
+                        "params": {
+                            "expected": "Array",
+                            "received": "none",
+                            "preview": "nothing"
+                        },
+                        "errors": []
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn it_previews_received_values() {
+        let message = |received: Option<&Json>| {
+            type_error("foo", "foo", JsonType::Integer, received)
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            message(None),
+            "foo must be of type `Integer`, but received nothing."
+        );
+        assert_eq!(
+            message(Some(&json!(null))),
+            "foo must be of type `Integer`, but received null."
+        );
+        assert_eq!(
+            message(Some(&json!(true))),
+            "foo must be of type `Integer`, but received Boolean(true)."
+        );
+        assert_eq!(
+            message(Some(&json!(1.5))),
+            "foo must be of type `Integer`, but received Number(1.5)."
+        );
+        assert_eq!(
+            message(Some(&json!("abc"))),
+            "foo must be of type `Integer`, but received String(\"abc\")."
+        );
+        assert_eq!(
+            message(Some(&json!([1, 2, 3]))),
+            "foo must be of type `Integer`, but received Array(3 items)."
+        );
+        assert_eq!(
+            message(Some(&json!({ "bar": 1 }))),
+            "foo must be of type `Integer`, but received Object(1 key)."
+        );
+        assert_eq!(
+            message(Some(&json!("abcdefghijklmnopqrstuvwxyz"))),
+            "foo must be of type `Integer`, but received String(\"abcdefghijklmnopqrst\"...)."
+        );
+    }
+
+    #[test]
+    fn it_deserializes_serialized_errors() {
+        let err = json_error(vec![
+            type_error("foo", "foo", JsonType::String, Some(&json!(1))),
+            json_error(vec![
+                test_error("min", "bar.baz", "bar.baz must be at least 5.")
+                    .with_measure(json!({ "min": 5 }), json!(4)),
+                test_error("no_good", "bar.qux", "Validation failed for bar.qux!"),
+            ]),
+        ]);
+        let serialized = serde_json::to_string(&err).unwrap();
+        let deserialized = serde_json::from_str::<ValidationError>(&serialized).unwrap();
+        assert_eq!(deserialized, err);
+        assert_eq!(
+            deserialized.flatten().last().map(ValidationError::code),
+            Some(&ErrorCode::Custom("no_good".into()))
+        );
+    }
+
+    #[test]
+    fn it_indents_nested_errors_in_reports() {
+        let err = json_error(vec![
+            test_error("min", "foo", "foo must be at least 5."),
+            json_error(vec![test_error(
+                "max",
+                "bar.baz",
+                "bar.baz must be at most 3.",
+            )]),
+        ]);
+        assert_eq!(
+            err.to_report(),
+            concat!(
+                "✗ 2 validation errors occurred.\n",
+                "  ✗ foo — foo must be at least 5.\n",
+                "  ✗ 1 validation error occurred.\n",
+                "    ✗ bar.baz — bar.baz must be at most 3.\n",
+            )
+        );
+    }
+
+    #[test]
+    fn it_builds_custom_errors_from_tests() {
+        let test = Test::new(
+            "passwords_match",
+            "<label> must match.",
+            Json::Null,
+            |passwords: &Vec<String>| match passwords.as_slice() {
+                [password, confirmation] if password == confirmation => Ok(true),
+                [_, _] => Err(
+                    ValidationError::new("passwords_match", "Passwords must match.")
+                        .with_path("passwords")
+                        .with_params(json!({ "fields": 2 }))
+                        .with_children(vec![test_error(
+                            "mismatch",
+                            "passwords[1]",
+                            "Confirmation does not match.",
+                        )]),
+                ),
+                _ => Ok(false),
+            },
+        );
+        let passwords = vec!["foo".to_string(), "bar".to_string()];
+        let err = test
+            .check("passwords", "passwords", &passwords)
+            .unwrap_err();
+        assert_eq!(err.code(), &ErrorCode::Custom("passwords_match".into()));
+        assert_eq!(
+            to_json(err).unwrap(),
+            json!({
+                "type": "passwords_match",
+                "path": "passwords",
+                "segments": ["passwords"],
+                "label": "passwords",
+                "message": "Passwords must match.",
+                "params": { "fields": 2 },
+                "errors": [{
+                    "type": "mismatch",
+                    "path": "passwords[1]",
+                    "segments": ["passwords", 1],
+                    "label": "passwords[1]",
+                    "message": "Confirmation does not match.",
+                    "errors": []
+                }]
+            })
+        );
+        assert_eq!(
+            test.check("passwords", "passwords", &vec!["foo".into()]),
+            Err(test_error(
+                "passwords_match",
+                "passwords",
+                "passwords must match."
+            ))
+        );
+    }
+
+    #[test]
+    fn it_builds_dynamic_messages_from_tests() {
+        let test = Test::new(
+            "plan_limit",
+            "<label> is not allowed.",
+            Json::Null,
+            |seats: &u64| {
+                Ok(match *seats {
+                    seats if seats <= 5 => TestOutcome::Pass,
+                    seats if seats > 100 => TestOutcome::Fail,
+                    seats => TestOutcome::FailWith(format!(
+                        "<label> of {} is not in the allowed set for plan 'basic'.",
+                        seats
+                    )),
+                })
+            },
+        );
+        assert_eq!(test.check("seats", "Seats", &3), Ok(()));
+        assert_eq!(
+            test.check("seats", "Seats", &17),
+            Err(test_error(
+                "plan_limit",
+                "seats",
+                "Seats of 17 is not in the allowed set for plan 'basic'."
+            )
+            .with_label("Seats")
+            .with_redacted_message("Seats is not allowed."))
+        );
+        assert_eq!(
+            test.check("seats", "Seats", &17)
+                .unwrap_err()
+                .redacted()
+                .message(),
+            "Seats is not allowed."
+        );
+        assert_eq!(
+            to_json(test.check("seats", "Seats", &101).unwrap_err()).unwrap(),
+            json!({
+                "type": "plan_limit",
+                "path": "seats",
+                "segments": ["seats"],
+                "label": "Seats",
+                "message": "Seats is not allowed.",
+                "errors": []
+            })
+        );
+    }
+
+    #[test]
+    fn it_groups_messages_by_field() {
+        let err = json_error(vec![
+            test_error(
+                "min_length",
+                "email",
+                "email must be at least 6 characters long.",
+            ),
+            test_error("matches", "email", "email must match the pattern '@'."),
+            test_error("passwords_match", "", "Passwords must match."),
+            test_error("min", "age", "age must be at least 18."),
+        ]);
+        assert_eq!(
+            err.to_field_map(),
+            json!({
+                "": ["Passwords must match."],
+                "age": ["age must be at least 18."],
+                "email": [
+                    "email must be at least 6 characters long.",
+                    "email must match the pattern '@'."
+                ]
+            })
+        );
+        assert_eq!(
+            err.by_field().keys().collect::<Vec<_>>(),
+            vec!["", "age", "email"]
+        );
+    }
+
+    #[test]
+    fn it_builds_paths_from_segments() {
+        let segments = vec![
+            PathSegment::Key("items".into()),
+            PathSegment::Index(0),
+            PathSegment::Key("unit.price".into()),
+        ];
+        assert_eq!(dotted_path(&segments), "items[0].unit.price");
+        let error = ValidationError::new("min", "too low").with_path_segments(segments.clone());
+        assert_eq!(error.path(), "items[0].unit.price");
+        assert_eq!(error.segments(), segments.as_slice());
+        assert_eq!(error.label(), "unit.price");
+        let error =
+            ValidationError::new("min", "too low").with_path_segments(segments[..2].to_vec());
+        assert_eq!(error.label(), "items[0]");
+    }
 }
@@ -0,0 +1,196 @@
+use super::{
+    json::Json,
+    object::ObjectSchema,
+    spec::{SchemaKind, SchemaSpec},
+};
+use std::collections::BTreeMap;
+
+/// Keys whose samples mix objects or arrays with other kinds are left undefined.
+pub fn infer(samples: &[Json]) -> ObjectSchema {
+    let objects = samples
+        .iter()
+        .filter(|sample| sample.is_object())
+        .collect::<Vec<&Json>>();
+    let spec = infer_spec(SchemaKind::Object, &objects);
+    ObjectSchema::from_spec(&spec).unwrap_or_else(|_| ObjectSchema::new())
+}
+
+/// Kinds that cannot be reconciled infer `None`, leaving the values unconstrained.
+fn infer_kind(values: &[&Json]) -> Option<SchemaKind> {
+    let kinds = values.iter().filter_map(|value| match value {
+        Json::Array(_) => Some(SchemaKind::Array),
+        Json::Bool(_) => Some(SchemaKind::Boolean),
+        Json::Number(number) if number.is_f64() => Some(SchemaKind::Float),
+        Json::Number(number) if number.is_u64() => Some(SchemaKind::Unsigned),
+        Json::Number(_) => Some(SchemaKind::Integer),
+        Json::Object(_) => Some(SchemaKind::Object),
+        Json::String(_) => Some(SchemaKind::String),
+        Json::Null => None,
+    });
+    kinds
+        .map(Some)
+        .reduce(|inferred, kind| match (inferred?, kind?) {
+            (inferred, kind) if inferred == kind => Some(inferred),
+            (SchemaKind::Array | SchemaKind::Object, _)
+            | (_, SchemaKind::Array | SchemaKind::Object) => None,
+            (SchemaKind::Float, SchemaKind::Integer | SchemaKind::Unsigned)
+            | (SchemaKind::Integer | SchemaKind::Unsigned, SchemaKind::Float) => {
+                Some(SchemaKind::Float)
+            }
+            (SchemaKind::Integer, SchemaKind::Unsigned)
+            | (SchemaKind::Unsigned, SchemaKind::Integer) => Some(SchemaKind::Integer),
+            _ => Some(SchemaKind::String),
+        })
+        .unwrap_or(Some(SchemaKind::String))
+}
+
+fn infer_spec(kind: SchemaKind, values: &[&Json]) -> SchemaSpec {
+    let mut spec = SchemaSpec {
+        kind,
+        label: None,
+        description: None,
+        optional: false,
+        nullable: values.iter().any(|value| value.is_null()),
+        sensitive: false,
+        default: None,
+        coercions: None,
+        transforms: vec![],
+        tests: vec![],
+        elements: None,
+        properties: BTreeMap::new(),
+    };
+    match kind {
+        SchemaKind::Array => {
+            let elements = values
+                .iter()
+                .filter_map(|value| value.as_array())
+                .flatten()
+                .collect::<Vec<&Json>>();
+            if let Some(kind) = infer_kind(&elements).filter(|_| !elements.is_empty()) {
+                spec.elements = Some(Box::new(infer_spec(kind, &elements)));
+            }
+        }
+        SchemaKind::Object => {
+            let objects = values
+                .iter()
+                .filter_map(|value| value.as_object())
+                .collect::<Vec<_>>();
+            let mut properties = BTreeMap::<&String, Vec<&Json>>::new();
+            objects
+                .iter()
+                .flat_map(|object| object.iter())
+                .for_each(|(key, value)| properties.entry(key).or_default().push(value));
+            spec.properties = properties
+                .into_iter()
+                .filter_map(|(key, values)| {
+                    let mut property = infer_spec(infer_kind(&values)?, &values);
+                    property.optional = values.len() < objects.len();
+                    Some((key.clone(), property))
+                })
+                .collect();
+        }
+        _ => (),
+    }
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{infer, OkSchema};
+    use serde_json::json;
+
+    #[test]
+    fn it_infers_a_schema_from_samples() {
+        let samples = vec![
+            json!({
+                "id": 1,
+                "name": "Ada",
+                "score": 9.5,
+                "tags": ["admin"],
+                "address": { "city": "London", "zip": null }
+            }),
+            json!({
+                "id": 2,
+                "name": "Grace",
+                "score": 10,
+                "offset": -3,
+                "tags": [],
+                "address": { "city": "Arlington", "zip": "22201" },
+                "manager": null
+            }),
+            json!({
+                "id": 3,
+                "name": "Edsger",
+                "score": 7,
+                "offset": 4,
+                "tags": ["ops", "oncall"],
+                "address": { "city": "Austin", "zip": "78701" },
+                "manager": { "id": 1 }
+            }),
+        ];
+        let schema = infer(&samples);
+        assert_eq!(
+            serde_json::to_value(schema.to_spec().unwrap()).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "address": {
+                        "type": "object",
+                        "properties": {
+                            "city": { "type": "string" },
+                            "zip": { "type": "string", "nullable": true }
+                        }
+                    },
+                    "id": { "type": "unsigned" },
+                    "manager": {
+                        "type": "object",
+                        "optional": true,
+                        "nullable": true,
+                        "properties": { "id": { "type": "unsigned" } }
+                    },
+                    "name": { "type": "string" },
+                    "offset": { "type": "integer", "optional": true },
+                    "score": { "type": "float" },
+                    "tags": { "type": "array", "elements": { "type": "string" } }
+                }
+            })
+        );
+        samples.into_iter().for_each(|sample| {
+            assert!(schema.validate(sample).is_ok());
+        });
+        let schema = schema.string("email", |email| email.min_length(3));
+        assert!(schema.validate(json!({ "email": "" })).is_err());
+    }
+
+    #[test]
+    fn it_leaves_conflicting_kinds_unconstrained() {
+        let samples = vec![
+            json!({ "a": true, "b": 1, "c": [1], "d": "x" }),
+            json!({ "a": [1], "b": "two", "c": ["x", { "y": 1 }], "d": false }),
+        ];
+        let schema = infer(&samples);
+        assert_eq!(
+            serde_json::to_value(schema.to_spec().unwrap()).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "b": { "type": "string" },
+                    "c": { "type": "array" },
+                    "d": { "type": "string" }
+                }
+            })
+        );
+        samples.into_iter().for_each(|sample| {
+            assert!(schema.validate(sample).is_ok());
+        });
+    }
+
+    #[test]
+    fn it_infers_an_empty_object_without_samples() {
+        let schema = infer(&[json!(1), json!("foo")]);
+        assert_eq!(
+            serde_json::to_value(schema.to_spec().unwrap()).unwrap(),
+            json!({ "type": "object" })
+        );
+    }
+}
@@ -1,16 +1,24 @@
-use super::error::{type_error, Result};
+use super::{
+    bytes::{self, Base64},
+    error::{
+        base64_error, byte_error, epoch_error, precision_error, range_error, type_error, Result,
+    },
+};
+use serde::{Deserialize, Serialize};
 pub use serde_json::{
-    from_value as from_json, map::Map, to_string, to_value as to_json, Value as Json,
+    from_value as from_json, map::Map, to_value as to_json, Number, Value as Json,
 };
+use std::borrow::Cow;
 
 pub type Object = Map<String, Json>;
 
 pub type Array = Vec<Json>;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum JsonType {
     Array,
     Boolean,
+    Bytes,
     Float,
     Integer,
     None,
@@ -21,108 +29,352 @@ pub enum JsonType {
     Unsigned,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Epoch {
+    Seconds,
+    Millis,
+}
+
+impl Epoch {
+    fn bounds(self) -> (i128, i128) {
+        match self {
+            Epoch::Seconds => (-9_223_372_036, 9_223_372_036),
+            Epoch::Millis => (-9_223_372_036_854, 9_223_372_036_854),
+        }
+    }
+
+    fn format(self, timestamp: i128) -> Option<String> {
+        let (min, max) = self.bounds();
+        if timestamp < min || timestamp > max {
+            return None;
+        }
+        Some(self.render(timestamp as i64))
+    }
+
+    fn render(self, timestamp: i64) -> String {
+        match self {
+            Epoch::Seconds => rfc3339(timestamp, None),
+            Epoch::Millis => rfc3339(timestamp.div_euclid(1000), Some(timestamp.rem_euclid(1000))),
+        }
+    }
+
+    fn range(self) -> (String, String) {
+        let (min, max) = self.bounds();
+        (self.render(min as i64), self.render(max as i64))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Coercions {
+    #[serde(default = "period", skip_serializing_if = "is_period")]
+    pub decimal_separator: char,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_separator: Option<char>,
+    #[serde(default = "allowed", skip_serializing_if = "is_allowed")]
+    pub string_to_number: bool,
+    #[serde(default = "allowed", skip_serializing_if = "is_allowed")]
+    pub number_to_string: bool,
+    #[serde(default = "allowed", skip_serializing_if = "is_allowed")]
+    pub string_to_boolean: bool,
+    #[serde(default = "allowed", skip_serializing_if = "is_allowed")]
+    pub boolean_to_string: bool,
+    #[serde(default, skip_serializing_if = "is_disallowed")]
+    pub number_to_boolean: bool,
+    #[serde(default, skip_serializing_if = "is_disallowed")]
+    pub truthy_strings: bool,
+    #[serde(default, skip_serializing_if = "is_disallowed")]
+    pub lenient_numbers: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+    #[serde(default, skip_serializing_if = "Base64::is_standard")]
+    pub base64: Base64,
+}
+
+impl Default for Coercions {
+    fn default() -> Self {
+        Coercions {
+            decimal_separator: '.',
+            group_separator: None,
+            string_to_number: true,
+            number_to_string: true,
+            string_to_boolean: true,
+            boolean_to_string: true,
+            number_to_boolean: false,
+            truthy_strings: false,
+            lenient_numbers: false,
+            epoch: None,
+            base64: Base64::Standard,
+        }
+    }
+}
+
+impl Coercions {
+    pub fn is_default(&self) -> bool {
+        self == &Coercions::default()
+    }
+
+    pub fn allow_string_to_number(mut self, allow: bool) -> Self {
+        self.string_to_number = allow;
+        self
+    }
+
+    pub fn allow_number_to_string(mut self, allow: bool) -> Self {
+        self.number_to_string = allow;
+        self
+    }
+
+    pub fn allow_string_to_boolean(mut self, allow: bool) -> Self {
+        self.string_to_boolean = allow;
+        self
+    }
+
+    pub fn allow_boolean_to_string(mut self, allow: bool) -> Self {
+        self.boolean_to_string = allow;
+        self
+    }
+
+    pub fn allow_number_to_boolean(mut self, allow: bool) -> Self {
+        self.number_to_boolean = allow;
+        self
+    }
+
+    pub fn allow_truthy_strings(mut self, allow: bool) -> Self {
+        self.truthy_strings = allow;
+        self
+    }
+
+    pub fn allow_lenient_numbers(mut self, allow: bool) -> Self {
+        self.lenient_numbers = allow;
+        self
+    }
+
+    fn numeric<'a>(&self, string: &'a str) -> Option<Cow<'a, str>> {
+        if !self.string_to_number {
+            return None;
+        }
+        if self.lenient_numbers && string.contains('_') {
+            let string = without_underscores(string)?;
+            return Some(Cow::Owned(self.separated(&string)?.into_owned()));
+        }
+        self.separated(string)
+    }
+
+    fn separated<'a>(&self, string: &'a str) -> Option<Cow<'a, str>> {
+        if self.decimal_separator == '.' && self.group_separator.is_none() {
+            return Some(Cow::Borrowed(string));
+        }
+        let (integral, fraction) = match string.split_once(self.decimal_separator) {
+            Some((integral, fraction)) => (integral, Some(fraction)),
+            None => (string, None),
+        };
+        let integral = match self.group_separator {
+            Some(group) if integral.contains(group) => ungroup(integral, group)?,
+            _ => integral.to_string(),
+        };
+        let is_separator = |c: char| c == '.' || c == ',';
+        if integral.contains(is_separator) || fraction.is_some_and(|f| f.contains(is_separator)) {
+            return None;
+        }
+        Some(Cow::Owned(match fraction {
+            Some(fraction) => format!("{}.{}", integral, fraction),
+            None => integral,
+        }))
+    }
+}
+
 impl JsonType {
-    pub fn coerce(&self, path: &str, label: &str, json: Json) -> Result<Json> {
+    pub fn coerce(
+        &self,
+        path: &str,
+        label: &str,
+        json: Json,
+        coercions: &Coercions,
+    ) -> Result<Json> {
         match self {
             JsonType::Boolean => {
                 if json.is_boolean() {
                     return Ok(json);
                 }
-                if json.is_string() {
-                    return match json.as_str().unwrap() {
+                if coercions.number_to_boolean {
+                    match (json.as_u64(), json.as_str()) {
+                        (Some(0), _) | (_, Some("0")) => return Ok(Json::Bool(false)),
+                        (Some(1), _) | (_, Some("1")) => return Ok(Json::Bool(true)),
+                        _ => {}
+                    }
+                }
+                if let (Some(string), true) = (json.as_str(), coercions.string_to_boolean) {
+                    if coercions.truthy_strings {
+                        return match string.to_ascii_lowercase().as_str() {
+                            "true" | "yes" | "on" => Ok(Json::Bool(true)),
+                            "false" | "no" | "off" => Ok(Json::Bool(false)),
+                            _ => Err(type_error(path, label, JsonType::Boolean, Some(&json))),
+                        };
+                    }
+                    return match string {
                         "true" => Ok(Json::Bool(true)),
                         "false" => Ok(Json::Bool(false)),
-                        _ => Err(type_error(path, label, JsonType::Boolean)),
+                        _ => Err(type_error(path, label, JsonType::Boolean, Some(&json))),
                     };
                 }
-                Err(type_error(path, label, JsonType::Boolean))
+                Err(type_error(path, label, JsonType::Boolean, Some(&json)))
             }
             JsonType::Integer => {
                 if json.is_i64() {
                     return Ok(json);
                 }
-                if json.is_f64() {
-                    let float = json.as_f64().unwrap();
+                if let (true, Some(float)) = (json.is_f64(), json.as_f64()) {
                     if float.fract() == 0.0 {
-                        return Ok(to_json::<i64>(float as i64).unwrap());
+                        if float < i64::MIN as f64 || float >= i64::MAX as f64 {
+                            return Err(range_error(path, label, JsonType::Integer, &json));
+                        }
+                        return Ok(Json::from(float as i64));
                     }
                 }
-                if json.is_u64() {
-                    let unsigned = json.as_u64().unwrap();
-                    if unsigned <= std::i64::MAX as u64 {
+                if let Some(unsigned) = json.as_u64() {
+                    if unsigned <= i64::MAX as u64 {
                         return Ok(json);
                     }
                 }
-                if json.is_string() {
-                    let string = json.as_str().unwrap();
+                if let Some(string) = json.as_str().and_then(|s| coercions.numeric(s)) {
+                    let string = trim_zero_fraction(string.as_ref());
+                    let expanded = coercions
+                        .lenient_numbers
+                        .then(|| expand_exponent(string))
+                        .flatten();
+                    let string = expanded.as_deref().unwrap_or(string);
                     if let Ok(integer) = string.parse::<i64>() {
-                        return Ok(to_json(integer).unwrap());
+                        return Ok(Json::from(integer));
+                    }
+                    if is_integral(string.strip_prefix('-').unwrap_or(string)) {
+                        return Err(range_error(path, label, JsonType::Integer, &json));
                     }
                 }
-                Err(type_error(path, label, JsonType::Integer))
+                Err(type_error(path, label, JsonType::Integer, Some(&json)))
             }
             JsonType::Unsigned => {
                 if json.is_u64() {
                     return Ok(json);
                 }
-                if json.is_f64() {
-                    let float = json.as_f64().unwrap();
+                if let (true, Some(float)) = (json.is_f64(), json.as_f64()) {
                     if float >= 0.0 && float.fract() == 0.0 {
-                        return Ok(to_json::<u64>(float as u64).unwrap());
+                        if float >= u64::MAX as f64 {
+                            return Err(range_error(path, label, JsonType::Unsigned, &json));
+                        }
+                        return Ok(Json::from(float as u64));
                     }
                 }
-                if json.is_i64() {
-                    let integer = json.as_i64().unwrap();
+                if let Some(integer) = json.as_i64() {
                     if integer >= 0 {
-                        return Ok(to_json::<u64>(integer as u64).unwrap());
+                        return Ok(Json::from(integer as u64));
                     }
                 }
-                if json.is_string() {
-                    let string = json.as_str().unwrap();
+                if let Some(string) = json.as_str().and_then(|s| coercions.numeric(s)) {
+                    let string = trim_zero_fraction(string.as_ref());
+                    let expanded = coercions
+                        .lenient_numbers
+                        .then(|| expand_exponent(string))
+                        .flatten();
+                    let string = expanded.as_deref().unwrap_or(string);
                     if let Ok(unsigned) = string.parse::<u64>() {
-                        return Ok(to_json(unsigned).unwrap());
+                        return Ok(Json::from(unsigned));
+                    }
+                    if is_integral(string) {
+                        return Err(range_error(path, label, JsonType::Unsigned, &json));
                     }
                 }
-                Err(type_error(path, label, JsonType::Unsigned))
+                Err(type_error(path, label, JsonType::Unsigned, Some(&json)))
             }
             JsonType::Float => {
-                if json.is_f64() || json.is_i64() {
+                if json.is_f64() {
                     return Ok(json);
                 }
-                if json.is_u64() {
-                    let unsigned = json.as_u64().unwrap();
-                    if unsigned <= std::f64::MAX as u64 {
-                        return Ok(to_json::<f64>(unsigned as f64).unwrap());
-                    }
+                if let Some(integer) = json.as_i64() {
+                    return match is_exact_float(integer.into()) {
+                        true => Ok(json),
+                        false => Err(precision_error(path, label, JsonType::Float, &json)),
+                    };
                 }
-                if json.is_string() {
-                    let string = json.as_str().unwrap();
-                    if let Ok(float) = string.parse::<f64>() {
-                        return Ok(to_json(float).unwrap());
+                if let Some(unsigned) = json.as_u64() {
+                    return match is_exact_float(unsigned.into()) {
+                        true => Ok(Json::from(unsigned as f64)),
+                        false => Err(precision_error(path, label, JsonType::Float, &json)),
+                    };
+                }
+                if let Some(string) = json.as_str().and_then(|s| coercions.numeric(s)) {
+                    let integer = string
+                        .parse::<i64>()
+                        .map(i128::from)
+                        .or_else(|_| string.parse::<u64>().map(i128::from));
+                    if let Ok(integer) = integer {
+                        if !is_exact_float(integer) {
+                            return Err(precision_error(path, label, JsonType::Float, &json));
+                        }
+                    }
+                    if let Some(float) = string.parse::<f64>().ok().and_then(Number::from_f64) {
+                        return Ok(Json::Number(float));
                     }
                 }
-                Err(type_error(path, label, JsonType::Float))
+                Err(type_error(path, label, JsonType::Float, Some(&json)))
             }
             JsonType::String => {
                 if json.is_string() {
                     return Ok(json);
                 }
-                if json.is_boolean() || json.is_number() {
-                    return Ok(to_json(json.to_string()).unwrap());
+                if let (Json::Number(number), Some(epoch)) = (&json, coercions.epoch) {
+                    let formatted = match timestamp(number) {
+                        Some(timestamp) => epoch.format(timestamp),
+                        None => return Err(type_error(path, label, JsonType::String, Some(&json))),
+                    };
+                    return match formatted {
+                        Some(formatted) => Ok(Json::String(formatted)),
+                        None => {
+                            let (min, max) = epoch.range();
+                            Err(epoch_error(path, label, &json, (&min, &max)))
+                        }
+                    };
+                }
+                if let (Some(boolean), true) = (json.as_bool(), coercions.boolean_to_string) {
+                    return Ok(Json::String(boolean.to_string()));
+                }
+                if let (Json::Number(number), true) = (&json, coercions.number_to_string) {
+                    return Ok(Json::String(normalize_number(number)));
+                }
+                Err(type_error(path, label, JsonType::String, Some(&json)))
+            }
+            JsonType::Bytes => {
+                if let Some(string) = json.as_str() {
+                    return match bytes::decode(string, coercions.base64) {
+                        Some(decoded) => Ok(Json::from(decoded)),
+                        None => Err(base64_error(path, label, &json)),
+                    };
+                }
+                if let Some(elements) = json.as_array() {
+                    let invalid = elements
+                        .iter()
+                        .position(|element| element.as_u64().is_none_or(|byte| byte > 255));
+                    return match invalid {
+                        Some(index) => Err(byte_error(
+                            &format!("{}[{}]", path, index),
+                            format!("{}[{}]", label, index),
+                            &elements[index],
+                        )),
+                        None => Ok(json),
+                    };
                 }
-                Err(type_error(path, label, JsonType::String))
+                Err(type_error(path, label, JsonType::Bytes, Some(&json)))
             }
             JsonType::Array => {
                 if json.is_array() {
                     return Ok(json);
                 }
-                Err(type_error(path, label, JsonType::Array))
+                Err(type_error(path, label, JsonType::Array, Some(&json)))
             }
             JsonType::Object => {
                 if json.is_object() {
                     return Ok(json);
                 }
-                Err(type_error(path, label, JsonType::Object))
+                Err(type_error(path, label, JsonType::Object, Some(&json)))
             }
             _ => Ok(json),
         }
@@ -132,6 +384,7 @@ impl JsonType {
         match self {
             JsonType::Array => "Array",
             JsonType::Boolean => "Boolean",
+            JsonType::Bytes => "Bytes",
             JsonType::Float => "Float",
             JsonType::Integer => "Integer",
             JsonType::None => "none",
@@ -144,22 +397,221 @@ impl JsonType {
     }
 }
 
+impl From<&Json> for JsonType {
+    fn from(json: &Json) -> JsonType {
+        match json {
+            Json::Array(_) => JsonType::Array,
+            Json::Bool(_) => JsonType::Boolean,
+            Json::Number(_) => JsonType::Number,
+            Json::Object(_) => JsonType::Object,
+            Json::String(_) => JsonType::String,
+            Json::Null => JsonType::Null,
+        }
+    }
+}
+
 impl From<&Option<Json>> for JsonType {
     fn from(value: &Option<Json>) -> JsonType {
         match value {
-            Some(json) => match json {
-                Json::Array(_) => JsonType::Array,
-                Json::Bool(_) => JsonType::Boolean,
-                Json::Number(_) => JsonType::Number,
-                Json::Object(_) => JsonType::Object,
-                Json::String(_) => JsonType::String,
-                Json::Null => JsonType::Null,
-            },
+            Some(json) => JsonType::from(json),
             None => JsonType::None,
         }
     }
 }
 
+fn normalize_number(number: &Number) -> String {
+    if let Some(integer) = number.as_i64() {
+        return integer.to_string();
+    }
+    if let Some(unsigned) = number.as_u64() {
+        return unsigned.to_string();
+    }
+    match number.as_f64().and_then(Number::from_f64) {
+        Some(float) => float.to_string(),
+        None => number.to_string(),
+    }
+}
+
+fn timestamp(number: &Number) -> Option<i128> {
+    if let Some(integer) = number.as_i64() {
+        return Some(integer.into());
+    }
+    if let Some(unsigned) = number.as_u64() {
+        return Some(unsigned.into());
+    }
+    number
+        .as_f64()
+        .filter(|float| float.is_finite() && float.fract() == 0.0)
+        .map(|float| float as i128)
+}
+
+fn rfc3339(seconds: i64, millis: Option<i64>) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let era = (days + 719_468).div_euclid(146_097);
+    let day_of_era = (days + 719_468).rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    let fraction = match millis {
+        Some(millis) => format!(".{:03}", millis),
+        None => String::new(),
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60,
+        fraction
+    )
+}
+
+fn period() -> char {
+    '.'
+}
+
+fn is_period(separator: &char) -> bool {
+    *separator == '.'
+}
+
+fn allowed() -> bool {
+    true
+}
+
+fn is_allowed(allow: &bool) -> bool {
+    *allow
+}
+
+fn is_disallowed(allow: &bool) -> bool {
+    !*allow
+}
+
+fn ungroup(integral: &str, group: char) -> Option<String> {
+    let (sign, digits) = match integral.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integral),
+    };
+    let groups = digits.split(group).collect::<Vec<&str>>();
+    let (first, rest) = groups.split_first()?;
+    if first.is_empty() || first.len() > 3 || !is_integral(first) {
+        return None;
+    }
+    if !rest
+        .iter()
+        .all(|group| group.len() == 3 && is_integral(group))
+    {
+        return None;
+    }
+    Some(format!("{}{}", sign, groups.concat()))
+}
+
+fn without_underscores(string: &str) -> Option<String> {
+    let bytes = string.as_bytes();
+    let is_digit_at = |index: Option<usize>| {
+        index
+            .and_then(|index| bytes.get(index))
+            .is_some_and(u8::is_ascii_digit)
+    };
+    let is_separating = bytes
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'_')
+        .all(|(index, _)| is_digit_at(index.checked_sub(1)) && is_digit_at(Some(index + 1)));
+    match is_separating {
+        true => Some(string.replace('_', "")),
+        false => None,
+    }
+}
+
+fn expand_exponent(string: &str) -> Option<String> {
+    let (mantissa, exponent) = string.split_once(['e', 'E'])?;
+    let exponent = exponent.parse::<i64>().ok()?;
+    if exponent.unsigned_abs() > MAX_EXPONENT {
+        return None;
+    }
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let (integral, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integral, fraction);
+    if !is_integral(&digits) || (integral.is_empty() && fraction.is_empty()) {
+        return None;
+    }
+    let point = integral.len() as i64 + exponent;
+    if point <= 0 {
+        return digits.bytes().all(|b| b == b'0').then(|| "0".to_string());
+    }
+    let point = point as usize;
+    if point >= digits.len() {
+        return Some(format!(
+            "{}{}{}",
+            sign,
+            digits,
+            "0".repeat(point - digits.len())
+        ));
+    }
+    let (whole, rest) = digits.split_at(point);
+    rest.bytes()
+        .all(|b| b == b'0')
+        .then(|| format!("{}{}", sign, whole))
+}
+
+const MAX_EXPONENT: u64 = 4096;
+
+fn is_exact_float(integer: i128) -> bool {
+    integer as f64 as i128 == integer
+}
+
+fn trim_zero_fraction(string: &str) -> &str {
+    match string.split_once('.') {
+        Some((integral, fraction))
+            if !fraction.is_empty() && fraction.bytes().all(|byte| byte == b'0') =>
+        {
+            integral
+        }
+        _ => string,
+    }
+}
+
+fn is_integral(string: &str) -> bool {
+    !string.is_empty() && string.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+pub fn preview(json: &Json) -> String {
+    match json {
+        Json::Array(array) => match array.len() {
+            1 => "Array(1 item)".into(),
+            len => format!("Array({} items)", len),
+        },
+        Json::Object(object) => match object.len() {
+            1 => "Object(1 key)".into(),
+            len => format!("Object({} keys)", len),
+        },
+        Json::String(string) if string.chars().count() > PREVIEW_LENGTH => {
+            let truncated = string.chars().take(PREVIEW_LENGTH).collect::<String>();
+            format!("String({:?}...)", truncated)
+        }
+        Json::String(string) => format!("String({:?})", string),
+        Json::Bool(boolean) => format!("Boolean({})", boolean),
+        Json::Number(number) => format!("Number({})", number),
+        Json::Null => "null".into(),
+    }
+}
+
+const PREVIEW_LENGTH: usize = 20;
+
 impl std::fmt::Display for JsonType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
         write!(f, "{}", self.as_str())
@@ -4,13 +4,28 @@ extern crate pretty_assertions;
 
 mod array;
 mod boolean;
+mod bytes;
+mod context;
+mod datetime;
+pub mod de;
+mod diff;
 mod error;
+mod infer;
+mod iso;
 mod json;
+mod migrate;
+mod ndjson;
 mod number;
 mod object;
+mod registry;
 mod schema;
+mod source;
+mod spec;
 mod string;
 mod test;
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub mod testing;
 mod validator;
 
 use self::test::Test;
@@ -19,8 +34,22 @@ use self::validator::Validator;
 pub use self::{
     array::array,
     boolean::boolean,
+    bytes::{bytes, Base64, Encoding},
+    context::{Coercion, Context, Options, PathFormat, Validated, ValidationObserver},
+    de::{DeserializeError, Streaming},
+    diff::{diff, Change, ChangeKind, SchemaDiff},
+    error::{
+        json_error, test_error, type_error, ErrorCode, PathSegment, ProblemOptions, ValidationError,
+    },
+    infer::infer,
+    json::{Coercions, Epoch, JsonType},
+    migrate::{Migrator, MigratorError},
+    ndjson::{LineError, LineResult, NdjsonLines},
     number::{float, integer, unsigned},
     object::object,
+    registry::SchemaRegistry,
     schema::OkSchema,
-    string::string,
+    spec::{from_spec, RuleSpec, SchemaKind, SchemaSpec, SpecError},
+    string::{string, PasswordRules},
+    test::TestOutcome,
 };
@@ -0,0 +1,226 @@
+use super::{
+    error::{json_error, migration_error, missing_migration_error, type_error, Result},
+    json::{Json, JsonType},
+    OkSchema,
+};
+use std::collections::BTreeMap;
+
+type StepFn = Box<dyn Fn(Json) -> std::result::Result<Json, String>>;
+
+struct Step {
+    to: u64,
+    migrate: StepFn,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MigratorError {
+    DuplicateStep(u64),
+    NotForward { from: u64, to: u64 },
+}
+
+impl std::fmt::Display for MigratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            MigratorError::DuplicateStep(from) => {
+                write!(f, "A migration from version {} already exists.", from)
+            }
+            MigratorError::NotForward { from, to } => write!(
+                f,
+                "Migration from version {} to {} must move to a later version.",
+                from, to
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigratorError {}
+
+pub struct Migrator {
+    version_path: String,
+    target: u64,
+    steps: BTreeMap<u64, Step>,
+}
+
+impl Migrator {
+    pub fn new(target: u64) -> Self {
+        Migrator {
+            version_path: "version".into(),
+            target,
+            steps: BTreeMap::new(),
+        }
+    }
+
+    pub fn version_path(&mut self, path: &str) -> &mut Self {
+        self.version_path = path.into();
+        self
+    }
+
+    pub fn add<F>(
+        &mut self,
+        from: u64,
+        to: u64,
+        migrate: F,
+    ) -> std::result::Result<&mut Self, MigratorError>
+    where
+        F: Fn(Json) -> std::result::Result<Json, String> + 'static,
+    {
+        if to <= from {
+            return Err(MigratorError::NotForward { from, to });
+        }
+        if self.steps.contains_key(&from) {
+            return Err(MigratorError::DuplicateStep(from));
+        }
+        self.steps.insert(
+            from,
+            Step {
+                to,
+                migrate: Box::new(migrate),
+            },
+        );
+        Ok(self)
+    }
+
+    pub fn migrate(&self, json: Json) -> Result<Json> {
+        let path = self.version_path.as_str();
+        let pointer = format!("/{}", path.replace('.', "/"));
+        let received = json.pointer(&pointer);
+        let mut version = match received.and_then(Json::as_u64) {
+            Some(version) => version,
+            None => {
+                let error = type_error(path, path, JsonType::Unsigned, received);
+                return Err(json_error(vec![error]));
+            }
+        };
+        let mut json = json;
+        while version != self.target {
+            let step = match self.steps.get(&version) {
+                Some(step) if step.to <= self.target => step,
+                _ => {
+                    let error = missing_migration_error(path, version, self.target);
+                    return Err(json_error(vec![error]));
+                }
+            };
+            json = (step.migrate)(json).map_err(|reason| {
+                json_error(vec![migration_error(path, version, step.to, &reason)])
+            })?;
+            if let Some(field) = json.pointer_mut(&pointer) {
+                *field = Json::from(step.to);
+            }
+            version = step.to;
+        }
+        Ok(json)
+    }
+
+    pub fn migrate_and_validate<S>(&self, schema: &S, json: Json) -> Result<Json>
+    where
+        S: OkSchema + ?Sized,
+    {
+        schema.validate(self.migrate(json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        error::{json_error, migration_error, missing_migration_error, type_error},
+        json::JsonType,
+        object, Migrator, MigratorError,
+    };
+    use serde_json::json;
+
+    fn migrator() -> Migrator {
+        let mut migrator = Migrator::new(3);
+        migrator
+            .add(1, 2, |mut json| {
+                let name = json["name"].take();
+                json["full_name"] = name;
+                Ok(json)
+            })
+            .unwrap()
+            .add(2, 3, |mut json| match json["full_name"].take() {
+                serde_json::Value::Null => Err("full_name is missing".into()),
+                full_name => {
+                    json["profile"] = json!({ "full_name": full_name });
+                    Ok(json)
+                }
+            })
+            .unwrap();
+        migrator
+    }
+
+    #[test]
+    fn it_migrates_and_validates() {
+        let schema = object()
+            .unsigned("version", |version| version)
+            .object("profile", |profile| {
+                profile.string("full_name", |full_name| full_name.min_length(1))
+            });
+        assert_eq!(
+            migrator().migrate_and_validate(&schema, json!({ "version": 1, "name": "Ada" })),
+            Ok(json!({ "version": 3, "profile": { "full_name": "Ada" } }))
+        );
+        assert_eq!(
+            migrator().migrate_and_validate(
+                &schema,
+                json!({ "version": 3, "profile": { "full_name": "Ada" } })
+            ),
+            Ok(json!({ "version": 3, "profile": { "full_name": "Ada" } }))
+        );
+        assert!(migrator()
+            .migrate_and_validate(&schema, json!({ "version": 1, "name": "" }))
+            .is_err());
+    }
+
+    #[test]
+    fn it_surfaces_failing_steps() {
+        assert_eq!(
+            migrator().migrate(json!({ "version": 2 })),
+            Err(json_error(vec![migration_error(
+                "version",
+                2,
+                3,
+                "full_name is missing"
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_reports_missing_migrations() {
+        assert_eq!(
+            migrator().migrate(json!({ "version": 0 })),
+            Err(json_error(vec![missing_migration_error("version", 0, 3)]))
+        );
+        assert_eq!(
+            migrator().migrate(json!({ "version": 4 })),
+            Err(json_error(vec![missing_migration_error("version", 4, 3)]))
+        );
+        let mut migrator = Migrator::new(2);
+        migrator.version_path("meta.version");
+        assert_eq!(
+            migrator.migrate(json!({ "meta": { "version": "1" } })),
+            Err(json_error(vec![type_error(
+                "meta.version",
+                "meta.version",
+                JsonType::Unsigned,
+                Some(&json!("1"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_steps() {
+        let mut extended = migrator();
+        assert_eq!(
+            extended.add(1, 3, Ok).err(),
+            Some(MigratorError::DuplicateStep(1))
+        );
+        assert_eq!(
+            extended.add(3, 3, Ok).err(),
+            Some(MigratorError::NotForward { from: 3, to: 3 })
+        );
+        assert_eq!(
+            extended.migrate(json!({ "version": 1, "name": "Ada" })),
+            migrator().migrate(json!({ "version": 1, "name": "Ada" }))
+        );
+    }
+}
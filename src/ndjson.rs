@@ -0,0 +1,144 @@
+use super::{error::ValidationError, json::Json, OkSchema};
+use std::io::{BufRead, Lines};
+
+#[derive(Debug, PartialEq)]
+pub enum LineError {
+    Io(String),
+    Parse(String),
+    Invalid(ValidationError),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LineResult {
+    pub line: usize,
+    pub result: std::result::Result<Json, LineError>,
+}
+
+pub struct NdjsonLines<'a, S: ?Sized, R> {
+    schema: &'a S,
+    lines: Lines<R>,
+    line: usize,
+    failures: usize,
+    max_failures: Option<usize>,
+}
+
+impl<'a, S: OkSchema + ?Sized, R: BufRead> NdjsonLines<'a, S, R> {
+    pub fn new(schema: &'a S, reader: R) -> Self {
+        NdjsonLines {
+            schema,
+            lines: reader.lines(),
+            line: 0,
+            failures: 0,
+            max_failures: None,
+        }
+    }
+
+    pub fn max_failures(mut self, max_failures: usize) -> Self {
+        self.max_failures = Some(max_failures);
+        self
+    }
+}
+
+impl<S: OkSchema + ?Sized, R: BufRead> Iterator for NdjsonLines<'_, S, R> {
+    type Item = LineResult;
+
+    fn next(&mut self) -> Option<LineResult> {
+        if let Some(max_failures) = self.max_failures {
+            if self.failures >= max_failures {
+                return None;
+            }
+        }
+        let (line, text) = loop {
+            self.line += 1;
+            match self.lines.next()? {
+                Ok(text) if text.trim().is_empty() => continue,
+                Ok(text) => break (self.line, text),
+                Err(err) => {
+                    self.failures += 1;
+                    return Some(LineResult {
+                        line: self.line,
+                        result: Err(LineError::Io(err.to_string())),
+                    });
+                }
+            }
+        };
+        let result = match serde_json::from_str::<Json>(&text) {
+            Ok(json) => self.schema.validate(json).map_err(LineError::Invalid),
+            Err(err) => Err(LineError::Parse(err.to_string())),
+        };
+        if result.is_err() {
+            self.failures += 1;
+        }
+        Some(LineResult { line, result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        error::{json_error, test_error},
+        object, LineError, LineResult, OkSchema,
+    };
+    use serde_json::json;
+
+    const FIXTURE: &str = r#"{ "id": 1, "name": "foo" }
+{ "id": 2, "name": "bar"
+
+{ "id": "3", "name": "" }
+{ "id": 4, "name": "qux" }
+"#;
+
+    fn schema() -> impl OkSchema {
+        object()
+            .unsigned("id", |id| id)
+            .string("name", |name| name.min_length(1))
+    }
+
+    #[test]
+    fn it_validates_each_line() {
+        let schema = schema();
+        let results = schema
+            .validate_ndjson(FIXTURE.as_bytes())
+            .collect::<Vec<LineResult>>();
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results[0],
+            LineResult {
+                line: 1,
+                result: Ok(json!({ "id": 1, "name": "foo" }))
+            }
+        );
+        assert_eq!(results[1].line, 2);
+        assert!(matches!(results[1].result, Err(LineError::Parse(_))));
+        assert_eq!(
+            results[2],
+            LineResult {
+                line: 4,
+                result: Err(LineError::Invalid(json_error(vec![test_error(
+                    "min_length",
+                    "name",
+                    "name must be at least 1 characters long."
+                )
+                .with_measure(json!({ "min": 1 }), json!(0))])))
+            }
+        );
+        assert_eq!(
+            results[3],
+            LineResult {
+                line: 5,
+                result: Ok(json!({ "id": 4, "name": "qux" }))
+            }
+        );
+    }
+
+    #[test]
+    fn it_stops_after_max_failures() {
+        let schema = schema();
+        let lines = schema
+            .validate_ndjson(FIXTURE.as_bytes())
+            .max_failures(1)
+            .map(|result| result.line)
+            .collect::<Vec<usize>>();
+        assert_eq!(lines, vec![1, 2]);
+    }
+}
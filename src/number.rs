@@ -1,9 +1,12 @@
 use super::{
+    context::Context,
     error::{ValidationError, ValidationResult},
-    json::{Json, JsonType},
-    OkSchema, Validator,
+    json::{Coercions, Json, JsonType},
+    spec::{RuleSpec, SchemaSpec, SpecError},
+    OkSchema, TestOutcome, Validator,
 };
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
 
 pub struct NumberSchema<N>
 where
@@ -22,15 +25,55 @@ where
         }
     }
 
+    pub fn test<M, F, O>(mut self, name: &'static str, message: M, test: F) -> Self
+    where
+        M: Into<String>,
+        F: Fn(&N) -> std::result::Result<O, ValidationError> + 'static,
+        O: Into<TestOutcome>,
+    {
+        self.validator
+            .add_test(name, message, Json::Null, test)
+            .custom();
+        self
+    }
+
+    pub fn default_value(mut self, value: N) -> Self {
+        self.validator.default = Some(json!(value));
+        self
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.validator.coercions = self.validator.coercions.allow_string_to_number(false);
+        self
+    }
+
+    pub fn lenient_parse(mut self) -> Self {
+        self.validator.coercions = self.validator.coercions.allow_lenient_numbers(true);
+        self
+    }
+
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.validator.coercions.decimal_separator = separator;
+        self
+    }
+
+    pub fn group_separator(mut self, separator: char) -> Self {
+        self.validator.coercions.group_separator = Some(separator);
+        self
+    }
+
     pub fn min(mut self, min: N) -> Self
     where
         N: 'static,
     {
-        self.validator.add_test(
-            "min",
-            format!("<label> must be at least {}.", min),
-            move |number| Ok(number >= &min),
-        );
+        self.validator
+            .add_test(
+                "min",
+                format!("<label> must be at least {}.", min),
+                json!({ "min": min }),
+                move |number| Ok(number >= &min),
+            )
+            .measure(|number| json!(number));
         self
     }
 
@@ -38,11 +81,14 @@ where
     where
         N: 'static,
     {
-        self.validator.add_test(
-            "max",
-            format!("<label> must be at most {}.", max),
-            move |number| Ok(number <= &max),
-        );
+        self.validator
+            .add_test(
+                "max",
+                format!("<label> must be at most {}.", max),
+                json!({ "max": max }),
+                move |number| Ok(number <= &max),
+            )
+            .measure(|number| json!(number));
         self
     }
 
@@ -50,11 +96,14 @@ where
     where
         N: 'static,
     {
-        self.validator.add_test(
-            "greater_than",
-            format!("<label> must be greater than {}.", limit),
-            move |number| Ok(number > &limit),
-        );
+        self.validator
+            .add_test(
+                "greater_than",
+                format!("<label> must be greater than {}.", limit),
+                json!({ "limit": limit }),
+                move |number| Ok(number > &limit),
+            )
+            .measure(|number| json!(number));
         self
     }
 
@@ -62,11 +111,161 @@ where
     where
         N: 'static,
     {
-        self.validator.add_test(
-            "less_than",
-            format!("<label> must be less than {}.", limit),
-            move |number| Ok(number < &limit),
+        self.validator
+            .add_test(
+                "less_than",
+                format!("<label> must be less than {}.", limit),
+                json!({ "limit": limit }),
+                move |number| Ok(number < &limit),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    /// Panics if `low` is greater than `high`.
+    pub fn between(mut self, low: N, high: N) -> Self
+    where
+        N: 'static,
+    {
+        assert!(low <= high, "between() requires low <= high");
+        self.validator
+            .add_test(
+                "between",
+                format!("<label> must be between {} and {}.", low, high),
+                json!({ "min": low, "max": high }),
+                move |number| Ok(number >= &low && number <= &high),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    /// Panics if `low` is greater than `high`.
+    pub fn between_exclusive(mut self, low: N, high: N) -> Self
+    where
+        N: 'static,
+    {
+        assert!(low <= high, "between_exclusive() requires low <= high");
+        self.validator
+            .add_test(
+                "between",
+                format!("<label> must be between {} and {}, exclusive.", low, high),
+                json!({ "min": low, "max": high, "exclusive": true }),
+                move |number| Ok(number > &low && number < &high),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    /// Panics if `min` is greater than `max`.
+    pub fn clamp(mut self, min: N, max: N) -> Self
+    where
+        N: Copy + 'static,
+    {
+        assert!(min <= max, "clamp() requires min <= max");
+        self.validator
+            .add_transform("clamp", move |number| match number {
+                number if number < min => min,
+                number if number > max => max,
+                number => number,
+            })
+            .params = json!({ "min": min, "max": max });
+        self
+    }
+
+    pub fn positive(self) -> Self
+    where
+        N: Default + 'static,
+    {
+        self.compare_to_zero("positive", "<label> must be positive.", |number, zero| {
+            number > zero
+        })
+    }
+
+    /// Always fails for `unsigned()` schemas, since no `u64` is below zero.
+    pub fn negative(self) -> Self
+    where
+        N: Default + 'static,
+    {
+        self.compare_to_zero("negative", "<label> must be negative.", |number, zero| {
+            number < zero
+        })
+    }
+
+    pub fn non_negative(self) -> Self
+    where
+        N: Default + 'static,
+    {
+        self.compare_to_zero(
+            "non_negative",
+            "<label> must not be negative.",
+            |number, zero| number >= zero,
+        )
+    }
+
+    /// Treats `-0.0` as zero for `float()` schemas.
+    pub fn non_zero(self) -> Self
+    where
+        N: Default + 'static,
+    {
+        self.compare_to_zero("non_zero", "<label> must not be zero.", |number, zero| {
+            number != zero
+        })
+    }
+
+    fn bounded(mut self, type_: &'static str, message: &'static str, min: N, max: N) -> Self
+    where
+        N: 'static,
+    {
+        self.validator
+            .add_test(
+                type_,
+                message,
+                json!({ "min": min, "max": max }),
+                move |number| Ok(number >= &min && number <= &max),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    fn compare_to_zero(
+        mut self,
+        type_: &'static str,
+        message: &'static str,
+        compare: fn(&N, &N) -> bool,
+    ) -> Self
+    where
+        N: Default + 'static,
+    {
+        let zero = N::default();
+        self.validator
+            .add_test(type_, message, Json::Null, move |number| {
+                Ok(compare(number, &zero))
+            })
+            .measure(|number| json!(number));
+        self
+    }
+
+    /// Floats are compared exactly, so `0.1 + 0.2` is not one of `[0.3]`.
+    pub fn one_of(mut self, values: Vec<N>) -> Self
+    where
+        N: 'static,
+    {
+        let message = format!(
+            "<label> must be one of the following: {}",
+            values
+                .iter()
+                .map(|value| format!("{}", value))
+                .collect::<Vec<String>>()
+                .join(", ")
         );
+        self.validator
+            .add_test(
+                "one_of",
+                message,
+                json!({ "values": values }),
+                move |number| Ok(values.iter().any(|value| value == number)),
+            )
+            .measure(|number| json!(number));
         self
     }
 
@@ -83,11 +282,380 @@ where
                 .join(", ")
         );
         self.validator
-            .add_test("not_one_of", message, move |number| {
-                Ok(!values.iter().any(|value| value == number))
-            });
+            .add_test(
+                "not_one_of",
+                message,
+                json!({ "values": values }),
+                move |number| Ok(!values.iter().any(|value| value == number)),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn coercions<F>(mut self, configure: F) -> Self
+    where
+        F: FnOnce(Coercions) -> Coercions,
+    {
+        self.validator.coercions = configure(self.validator.coercions);
+        self
+    }
+
+    pub fn from_spec(spec: &SchemaSpec) -> Result<Self, SpecError>
+    where
+        N: NumberRules + Default + Copy + 'static,
+    {
+        let mut schema = NumberSchema::new(spec.kind.json_type());
+        schema.validator.load_spec(spec);
+        let schema =
+            spec.transforms
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "clamp" => {
+                        let (min, max) = ordered_bounds(rule)?;
+                        Ok(schema.clamp(min, max))
+                    }
+                    _ => N::transform_from_rule(schema, rule),
+                })?;
+        let mut schema =
+            spec.tests
+                .iter()
+                .try_fold(schema, |schema, rule| match rule.name.as_str() {
+                    "min" => Ok(schema.min(rule.param("min")?)),
+                    "max" => Ok(schema.max(rule.param("max")?)),
+                    "greater_than" => Ok(schema.greater_than(rule.param("limit")?)),
+                    "less_than" => Ok(schema.less_than(rule.param("limit")?)),
+                    "between" if rule.params.get("exclusive").is_some() => {
+                        let (min, max) = ordered_bounds(rule)?;
+                        Ok(schema.between_exclusive(min, max))
+                    }
+                    "between" => {
+                        let (min, max) = ordered_bounds(rule)?;
+                        Ok(schema.between(min, max))
+                    }
+                    "one_of" => Ok(schema.one_of(rule.param("values")?)),
+                    "not_one_of" => Ok(schema.not_one_of(rule.param("values")?)),
+                    "positive" => Ok(schema.positive()),
+                    "negative" => Ok(schema.negative()),
+                    "non_negative" => Ok(schema.non_negative()),
+                    "non_zero" => Ok(schema.non_zero()),
+                    _ => N::test_from_rule(schema, rule),
+                })?;
+        schema.validator.load_messages(&spec.tests);
+        Ok(schema)
+    }
+}
+
+fn ordered_bounds<N>(rule: &RuleSpec) -> Result<(N, N), SpecError>
+where
+    N: DeserializeOwned + PartialOrd,
+{
+    let (min, max) = (rule.param::<N>("min")?, rule.param::<N>("max")?);
+    match min <= max {
+        true => Ok((min, max)),
+        false => Err(SpecError::InvalidParams(format!("{}.max", rule.name))),
+    }
+}
+
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+impl NumberSchema<u64> {
+    pub fn safe_integer(mut self) -> Self {
+        self.validator
+            .add_test(
+                "safe_integer",
+                "<label> must be a safe integer, since JavaScript cannot represent integers beyond 2^53 - 1 exactly.",
+                json!({ "max": MAX_SAFE_INTEGER }),
+                |number| Ok(*number <= MAX_SAFE_INTEGER as u64),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn port(self) -> Self {
+        self.bounded(
+            "port",
+            "<label> must be a port number between 1 and 65535.",
+            1,
+            65535,
+        )
+    }
+
+    pub fn even(mut self) -> Self {
+        self.validator
+            .add_test(
+                "even",
+                "<label> must be an even number.",
+                Json::Null,
+                |number| Ok(number % 2 == 0),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn odd(mut self) -> Self {
+        self.validator
+            .add_test(
+                "odd",
+                "<label> must be an odd number.",
+                Json::Null,
+                |number| Ok(number % 2 != 0),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+}
+
+impl NumberSchema<i64> {
+    /// Saturates, so `i64::MIN` becomes `i64::MAX`.
+    pub fn abs(mut self) -> Self {
+        self.validator.add_transform("abs", i64::saturating_abs);
+        self
+    }
+
+    /// Saturates, so `i64::MIN` becomes `i64::MAX`.
+    pub fn negate(mut self) -> Self {
+        self.validator.add_transform("negate", i64::saturating_neg);
+        self
+    }
+
+    pub fn safe_integer(mut self) -> Self {
+        self.validator
+            .add_test(
+                "safe_integer",
+                "<label> must be a safe integer, since JavaScript cannot represent integers beyond 2^53 - 1 exactly.",
+                json!({ "max": MAX_SAFE_INTEGER }),
+                |number| Ok(number.unsigned_abs() <= MAX_SAFE_INTEGER as u64),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn percent(self) -> Self {
+        self.bounded(
+            "percent",
+            "<label> must be a percentage between 0 and 100.",
+            0,
+            100,
+        )
+    }
+
+    pub fn even(mut self) -> Self {
+        self.validator
+            .add_test(
+                "even",
+                "<label> must be an even number.",
+                Json::Null,
+                |number| Ok(number % 2 == 0),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn odd(mut self) -> Self {
+        self.validator
+            .add_test(
+                "odd",
+                "<label> must be an odd number.",
+                Json::Null,
+                |number| Ok(number % 2 != 0),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+}
+
+impl NumberSchema<f64> {
+    pub fn abs(mut self) -> Self {
+        self.validator.add_transform("abs", f64::abs);
+        self
+    }
+
+    pub fn negate(mut self) -> Self {
+        self.validator
+            .add_transform("negate", |number: f64| -number);
         self
     }
+
+    pub fn latitude(self) -> Self {
+        self.bounded(
+            "latitude",
+            "<label> must be a latitude between -90 and 90.",
+            -90.0,
+            90.0,
+        )
+    }
+
+    pub fn longitude(self) -> Self {
+        self.bounded(
+            "longitude",
+            "<label> must be a longitude between -180 and 180.",
+            -180.0,
+            180.0,
+        )
+    }
+
+    /// Rounds half away from zero, like `f64::round`.
+    pub fn round(mut self) -> Self {
+        self.validator.add_transform("round", f64::round);
+        self
+    }
+
+    pub fn floor(mut self) -> Self {
+        self.validator.add_transform("floor", f64::floor);
+        self
+    }
+
+    pub fn ceil(mut self) -> Self {
+        self.validator.add_transform("ceil", f64::ceil);
+        self
+    }
+
+    /// Rounds half away from zero at the given precision. The binary value is
+    /// rounded, not its decimal text, so `1.005` (stored as `1.00499...`)
+    /// becomes `1.0` with two places.
+    pub fn round_to(mut self, places: u32) -> Self {
+        let scale = 10_f64.powi(places as i32);
+        self.validator
+            .add_transform("round_to", move |number| (number * scale).round() / scale)
+            .params = json!({ "places": places });
+        self
+    }
+
+    pub fn finite(mut self) -> Self {
+        self.validator.add_test(
+            "finite",
+            "<label> must be a finite number.",
+            Json::Null,
+            |number| Ok(number.is_finite()),
+        );
+        self
+    }
+
+    /// Counts digits in the shortest decimal form that round-trips the value.
+    pub fn decimal_places(mut self, places: u32) -> Self {
+        self.validator
+            .add_test(
+                "decimal_places",
+                format!("<label> must have at most {} decimal places.", places),
+                json!({ "max": places }),
+                move |number| Ok(decimal_places(*number) <= places as usize),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+
+    pub fn max_significant_digits(mut self, digits: u32) -> Self {
+        self.validator
+            .add_test(
+                "max_significant_digits",
+                format!("<label> must have at most {} significant digits.", digits),
+                json!({ "max": digits }),
+                move |number| Ok(significant_digits(*number) <= digits as usize),
+            )
+            .measure(|number| json!(number));
+        self
+    }
+}
+
+pub trait NumberRules: Serialize + DeserializeOwned + PartialOrd + std::fmt::Display {
+    fn transform_from_rule(
+        _schema: NumberSchema<Self>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<Self>, SpecError> {
+        Err(SpecError::UnknownRule(rule.name.clone()))
+    }
+
+    fn test_from_rule(
+        _schema: NumberSchema<Self>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<Self>, SpecError> {
+        Err(SpecError::UnknownRule(rule.name.clone()))
+    }
+}
+
+impl NumberRules for i64 {
+    fn transform_from_rule(
+        schema: NumberSchema<i64>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<i64>, SpecError> {
+        match rule.name.as_str() {
+            "abs" => Ok(schema.abs()),
+            "negate" => Ok(schema.negate()),
+            name => Err(SpecError::UnknownRule(name.into())),
+        }
+    }
+
+    fn test_from_rule(
+        schema: NumberSchema<i64>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<i64>, SpecError> {
+        match rule.name.as_str() {
+            "percent" => Ok(schema.percent()),
+            "safe_integer" => Ok(schema.safe_integer()),
+            "even" => Ok(schema.even()),
+            "odd" => Ok(schema.odd()),
+            name => Err(SpecError::UnknownRule(name.into())),
+        }
+    }
+}
+
+impl NumberRules for u64 {
+    fn test_from_rule(
+        schema: NumberSchema<u64>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<u64>, SpecError> {
+        match rule.name.as_str() {
+            "port" => Ok(schema.port()),
+            "safe_integer" => Ok(schema.safe_integer()),
+            "even" => Ok(schema.even()),
+            "odd" => Ok(schema.odd()),
+            name => Err(SpecError::UnknownRule(name.into())),
+        }
+    }
+}
+
+impl NumberRules for f64 {
+    fn transform_from_rule(
+        schema: NumberSchema<f64>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<f64>, SpecError> {
+        match rule.name.as_str() {
+            "round" => Ok(schema.round()),
+            "floor" => Ok(schema.floor()),
+            "ceil" => Ok(schema.ceil()),
+            "round_to" => Ok(schema.round_to(rule.param("places")?)),
+            "abs" => Ok(schema.abs()),
+            "negate" => Ok(schema.negate()),
+            name => Err(SpecError::UnknownRule(name.into())),
+        }
+    }
+
+    fn test_from_rule(
+        schema: NumberSchema<f64>,
+        rule: &RuleSpec,
+    ) -> Result<NumberSchema<f64>, SpecError> {
+        match rule.name.as_str() {
+            "finite" => Ok(schema.finite()),
+            "decimal_places" => Ok(schema.decimal_places(rule.param("max")?)),
+            "max_significant_digits" => Ok(schema.max_significant_digits(rule.param("max")?)),
+            "latitude" => Ok(schema.latitude()),
+            "longitude" => Ok(schema.longitude()),
+            name => Err(SpecError::UnknownRule(name.into())),
+        }
+    }
+}
+
+fn decimal_places(number: f64) -> usize {
+    let decimal = number.to_string();
+    decimal
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len())
+}
+
+fn significant_digits(number: f64) -> usize {
+    let scientific = format!("{:e}", number);
+    let mantissa = scientific.split('e').next().unwrap_or_default();
+    mantissa.bytes().filter(u8::is_ascii_digit).count()
 }
 
 impl<N> OkSchema for NumberSchema<N>
@@ -95,12 +663,12 @@ where
     N: Serialize + DeserializeOwned + PartialOrd + std::fmt::Display,
 {
     fn label(mut self, label: &'static str) -> Self {
-        self.validator.label = Some(label);
+        self.validator.label = Some(label.into());
         self
     }
 
     fn desc(mut self, description: &'static str) -> Self {
-        self.validator.description = Some(description);
+        self.validator.description = Some(description.into());
         self
     }
 
@@ -114,13 +682,28 @@ where
         self
     }
 
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
     fn validate_at(
         &self,
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>> {
-        self.validator.exec(path, value, all_errors)
+        self.validator.exec(path, value, all_errors, context)
+    }
+
+    fn to_spec(&self) -> Result<SchemaSpec, SpecError> {
+        self.validator.to_spec()
     }
 }
 
@@ -139,10 +722,10 @@ pub fn unsigned() -> NumberSchema<u64> {
 #[cfg(test)]
 mod tests {
     use super::super::{
-        error::{json_error, test_error, type_error},
-        float, integer,
-        json::JsonType,
-        unsigned, OkSchema,
+        error::{json_error, precision_error, range_error, test_error, type_error},
+        float, from_spec, integer,
+        json::{to_json, JsonType},
+        object, unsigned, ErrorCode, OkSchema, SchemaSpec, SpecError, TestOutcome, ValidationError,
     };
     use serde_json::json;
 
@@ -157,31 +740,66 @@ mod tests {
         assert_eq!(schema.validate(json!("-1")), Ok(json!(-1)));
         assert_eq!(
             schema.validate(json!(1.1)),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!(1.1))
+            )]))
         );
         assert_eq!(
-            schema.validate(json!(std::i64::MAX as u64 + 1)),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            schema.validate(json!(i64::MAX as u64 + 1)),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!(i64::MAX as u64 + 1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!(null))
+            )]))
         );
         assert_eq!(
             schema.validate(json!([])),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!([]))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(true)),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!(true))
+            )]))
         );
         assert_eq!(
             schema.validate(json!({})),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!({}))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Integer)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!("foo"))
+            )]))
         );
     }
 
@@ -193,35 +811,75 @@ mod tests {
         assert_eq!(schema.validate(json!("1")), Ok(json!(1)));
         assert_eq!(
             schema.validate(json!(1.1)),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!(1.1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(-1.0)),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!(-1.0))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(-1)),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!(-1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
-        );
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!(null))
+            )]))
+        );
         assert_eq!(
             schema.validate(json!([])),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!([]))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(true)),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!(true))
+            )]))
         );
         assert_eq!(
             schema.validate(json!({})),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!({}))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Unsigned)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!("foo"))
+            )]))
         );
     }
 
@@ -237,28 +895,318 @@ mod tests {
         assert_eq!(schema.validate(json!("-1")), Ok(json!(-1.0)));
         assert_eq!(schema.validate(json!("-1.0")), Ok(json!(-1.0)));
         assert_eq!(
-            schema.validate(json!(std::i64::MAX as u64 + 1)),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            schema.validate(json!(i64::MAX as u64 + 1)),
+            Ok(json!(i64::MAX as u64 as f64 + 1.0))
         );
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!(null))
+            )]))
         );
         assert_eq!(
             schema.validate(json!([])),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!([]))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(true)),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!(true))
+            )]))
         );
         assert_eq!(
             schema.validate(json!({})),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!({}))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!("foo"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_coerces_integer_edge_cases() {
+        let schema = integer();
+        assert_eq!(schema.validate(json!(-0.0)), Ok(json!(0)));
+        assert_eq!(
+            schema.validate(json!("-9223372036854775808")),
+            Ok(json!(i64::MIN))
+        );
+        assert_eq!(schema.validate(json!(i64::MIN as f64)), Ok(json!(i64::MIN)));
+        ["9223372036854775808", "-9223372036854775809"]
+            .iter()
+            .for_each(|string| {
+                assert_eq!(
+                    schema.validate(json!(string)),
+                    Err(json_error(vec![range_error(
+                        "",
+                        "",
+                        JsonType::Integer,
+                        &json!(string)
+                    )]))
+                );
+            });
+        assert_eq!(
+            schema.validate(json!(1e19)),
+            Err(json_error(vec![range_error(
+                "",
+                "",
+                JsonType::Integer,
+                &json!(1e19)
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!("  42")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!("  42"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_coerces_whole_number_strings_with_a_zero_fraction() {
+        let schema = integer();
+        assert_eq!(schema.validate(json!("1.0")), Ok(json!(1)));
+        assert_eq!(schema.validate(json!("-2.00")), Ok(json!(-2)));
+        for string in &["1.5", "1.", ".0", "1.0.0"] {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Integer,
+                    Some(&json!(string))
+                )])),
+                "{}",
+                string
+            );
+        }
+        assert_eq!(
+            schema.validate(json!("9223372036854775808.0")),
+            Err(json_error(vec![range_error(
+                "",
+                "",
+                JsonType::Integer,
+                &json!("9223372036854775808.0")
+            )]))
+        );
+        let schema = unsigned();
+        assert_eq!(schema.validate(json!("7.0")), Ok(json!(7)));
+        assert!(schema.validate(json!("-7.0")).is_err());
+        assert!(schema.validate(json!("18446744073709551616.0")).is_err());
+        let schema = integer().decimal_separator(',');
+        assert_eq!(schema.validate(json!("3,0")), Ok(json!(3)));
+    }
+
+    #[test]
+    fn it_coerces_unsigned_edge_cases() {
+        let schema = unsigned();
+        assert_eq!(schema.validate(json!(-0.0)), Ok(json!(0)));
+        assert_eq!(
+            schema.validate(json!("18446744073709551615")),
+            Ok(json!(u64::MAX))
+        );
+        assert_eq!(
+            schema.validate(json!("18446744073709551616")),
+            Err(json_error(vec![range_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                &json!("18446744073709551616")
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!(1e20)),
+            Err(json_error(vec![range_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                &json!(1e20)
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!("-1")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!("-1"))
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!("  42")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Unsigned,
+                Some(&json!("  42"))
+            )]))
+        );
+        let error = schema.validate(json!("18446744073709551616")).unwrap_err();
+        assert_eq!(
+            error.flatten().next().map(ValidationError::code),
+            Some(&ErrorCode::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn it_rejects_integers_that_lose_precision_as_floats() {
+        let schema = float();
+        let limit = 1_i64 << 53;
+        for integer in &[limit - 1, limit, limit + 2, -limit, -limit - 2] {
+            assert_eq!(
+                schema.validate(json!(integer)),
+                Ok(json!(*integer as f64)),
+                "{}",
+                integer
+            );
+        }
+        for integer in &[json!(limit + 1), json!(-limit - 1), json!(i64::MAX)] {
+            assert_eq!(
+                schema.validate(integer.clone()),
+                Err(json_error(vec![precision_error(
+                    "",
+                    "",
+                    JsonType::Float,
+                    integer
+                )])),
+                "{}",
+                integer
+            );
+        }
+        let unsigned = json!((limit + 1) as u64);
+        assert_eq!(
+            schema.validate(unsigned.clone()),
+            Err(json_error(vec![precision_error(
+                "",
+                "",
+                JsonType::Float,
+                &unsigned
+            )]))
+        );
+        let string = json!((limit + 1).to_string());
+        assert_eq!(
+            schema.validate(string.clone()),
+            Err(json_error(vec![precision_error(
+                "",
+                "",
+                JsonType::Float,
+                &string
+            )]))
+        );
+        assert_eq!(
+            schema.validate(json!(limit.to_string())),
+            Ok(json!(limit as f64))
+        );
+    }
+
+    #[test]
+    fn it_checks_precision_of_unsigned_integers_above_i64_max() {
+        let schema = float();
+        let exact = 1_u64 << 63;
+        assert_eq!(schema.validate(json!(exact)), Ok(json!(exact as f64)));
+        assert_eq!(
+            schema.validate(json!(exact.to_string())),
+            Ok(json!(exact as f64))
+        );
+        for inexact in &[
+            json!(u64::MAX),
+            json!(u64::MAX.to_string()),
+            json!(exact + 1),
+        ] {
+            assert_eq!(
+                schema.validate(inexact.clone()),
+                Err(json_error(vec![precision_error(
+                    "",
+                    "",
+                    JsonType::Float,
+                    inexact
+                )])),
+                "{}",
+                inexact
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_non_finite_floats() {
+        let schema = float();
+        ["inf", "-inf", "nan", "NaN", "1e999"]
+            .iter()
+            .for_each(|string| {
+                assert_eq!(
+                    schema.validate(json!(string)),
+                    Err(json_error(vec![type_error(
+                        "",
+                        "",
+                        JsonType::Float,
+                        Some(&json!(string))
+                    )]))
+                );
+            });
+        assert_eq!(schema.validate(json!("1e10")), Ok(json!(1e10)));
+    }
+
+    #[test]
+    fn it_requires_a_finite_result() {
+        let schema = float().label("Amount").round_to(400).finite();
+        assert_eq!(
+            schema.validate(json!(1.5)),
+            Err(json_error(vec![test_error(
+                "finite",
+                "",
+                "Amount must be a finite number."
+            )
+            .with_label("Amount")]))
+        );
+        let schema = float().label("Amount").finite();
+        assert_eq!(schema.validate(json!("1.5")), Ok(json!(1.5)));
+        assert_eq!(
+            schema.validate(json!("-infinity")),
+            Err(json_error(vec![type_error(
+                "",
+                "Amount",
+                JsonType::Float,
+                Some(&json!("-infinity"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_overrides_a_test_message() {
+        let schema = integer()
+            .label("Age")
+            .min(18)
+            .message("<label> is too young");
+        assert_eq!(
+            schema.validate(json!(12)),
+            Err(json_error(vec![test_error("min", "", "Age is too young")
+                .with_label("Age")
+                .with_measure(json!({ "min": 18 }), json!(12))]))
         );
     }
 
@@ -276,7 +1224,9 @@ mod tests {
                 "min",
                 "",
                 "u64 must be at least 5."
-            )]))
+            )
+            .with_label("u64")
+            .with_measure(json!({ "min": 5 }), json!(4))]))
         );
         assert_eq!(
             i.validate(json!(4)),
@@ -284,7 +1234,9 @@ mod tests {
                 "min",
                 "",
                 "i64 must be at least 5."
-            )]))
+            )
+            .with_label("i64")
+            .with_measure(json!({ "min": 5 }), json!(4))]))
         );
         assert_eq!(
             f.validate(json!(4.0)),
@@ -292,7 +1244,9 @@ mod tests {
                 "min",
                 "",
                 "f64 must be at least 5."
-            )]))
+            )
+            .with_label("f64")
+            .with_measure(json!({ "min": 5.0 }), json!(4.0))]))
         );
     }
 
@@ -310,7 +1264,9 @@ mod tests {
                 "max",
                 "",
                 "u64 must be at most 5."
-            )]))
+            )
+            .with_label("u64")
+            .with_measure(json!({ "max": 5 }), json!(6))]))
         );
         assert_eq!(
             i.validate(json!(6)),
@@ -318,7 +1274,9 @@ mod tests {
                 "max",
                 "",
                 "i64 must be at most 5."
-            )]))
+            )
+            .with_label("i64")
+            .with_measure(json!({ "max": 5 }), json!(6))]))
         );
         assert_eq!(
             f.validate(json!(6.0)),
@@ -326,7 +1284,9 @@ mod tests {
                 "max",
                 "",
                 "f64 must be at most 5."
-            )]))
+            )
+            .with_label("f64")
+            .with_measure(json!({ "max": 5.0 }), json!(6.0))]))
         );
     }
 
@@ -344,7 +1304,9 @@ mod tests {
                 "greater_than",
                 "",
                 "u64 must be greater than 5."
-            )]))
+            )
+            .with_label("u64")
+            .with_measure(json!({ "limit": 5 }), json!(5))]))
         );
         assert_eq!(
             i.validate(json!(5)),
@@ -352,7 +1314,9 @@ mod tests {
                 "greater_than",
                 "",
                 "i64 must be greater than 5."
-            )]))
+            )
+            .with_label("i64")
+            .with_measure(json!({ "limit": 5 }), json!(5))]))
         );
         assert_eq!(
             f.validate(json!(5.0)),
@@ -360,7 +1324,9 @@ mod tests {
                 "greater_than",
                 "",
                 "f64 must be greater than 5."
-            )]))
+            )
+            .with_label("f64")
+            .with_measure(json!({ "limit": 5.0 }), json!(5.0))]))
         );
     }
 
@@ -378,7 +1344,9 @@ mod tests {
                 "less_than",
                 "",
                 "u64 must be less than 5."
-            )]))
+            )
+            .with_label("u64")
+            .with_measure(json!({ "limit": 5 }), json!(5))]))
         );
         assert_eq!(
             i.validate(json!(5)),
@@ -386,7 +1354,9 @@ mod tests {
                 "less_than",
                 "",
                 "i64 must be less than 5."
-            )]))
+            )
+            .with_label("i64")
+            .with_measure(json!({ "limit": 5 }), json!(5))]))
         );
         assert_eq!(
             f.validate(json!(5.0)),
@@ -394,7 +1364,817 @@ mod tests {
                 "less_than",
                 "",
                 "f64 must be less than 5."
+            )
+            .with_label("f64")
+            .with_measure(json!({ "limit": 5.0 }), json!(5.0))]))
+        );
+    }
+
+    #[test]
+    fn it_sets_an_inclusive_range() {
+        let u = unsigned().label("u64").between(1, 10);
+        let i = integer().label("i64").between(-10, 10);
+        let f = float().label("f64").between(0.5, 1.5);
+        assert_eq!(u.validate(json!(1)), Ok(json!(1)));
+        assert_eq!(u.validate(json!(10)), Ok(json!(10)));
+        assert_eq!(i.validate(json!(-10)), Ok(json!(-10)));
+        assert_eq!(f.validate(json!(1.5)), Ok(json!(1.5)));
+        assert_eq!(
+            u.validate(json!(1000)),
+            Err(json_error(vec![test_error(
+                "between",
+                "",
+                "u64 must be between 1 and 10."
+            )
+            .with_label("u64")
+            .with_measure(json!({ "min": 1, "max": 10 }), json!(1000))]))
+        );
+        assert_eq!(
+            i.validate(json!(-11)),
+            Err(json_error(vec![test_error(
+                "between",
+                "",
+                "i64 must be between -10 and 10."
+            )
+            .with_label("i64")
+            .with_measure(json!({ "min": -10, "max": 10 }), json!(-11))]))
+        );
+        assert_eq!(
+            f.validate(json!(0.25)),
+            Err(json_error(vec![test_error(
+                "between",
+                "",
+                "f64 must be between 0.5 and 1.5."
+            )
+            .with_label("f64")
+            .with_measure(json!({ "min": 0.5, "max": 1.5 }), json!(0.25))]))
+        );
+    }
+
+    #[test]
+    fn it_sets_an_exclusive_range() {
+        let schema = integer().label("Rating").between_exclusive(0, 6);
+        assert_eq!(schema.validate(json!(1)), Ok(json!(1)));
+        assert_eq!(schema.validate(json!(5)), Ok(json!(5)));
+        for rating in &[0, 6] {
+            assert_eq!(
+                schema.validate(json!(rating)),
+                Err(json_error(vec![test_error(
+                    "between",
+                    "",
+                    "Rating must be between 0 and 6, exclusive."
+                )
+                .with_label("Rating")
+                .with_measure(
+                    json!({ "min": 0, "max": 6, "exclusive": true }),
+                    json!(rating)
+                )]))
+            );
+        }
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!(6)).is_err());
+        assert_eq!(schema.validate(json!(5)), Ok(json!(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "between() requires low <= high")]
+    fn it_rejects_an_inverted_range() {
+        integer().between(10, 1);
+    }
+
+    #[test]
+    fn it_rejects_an_inverted_range_in_specs() {
+        for params in &[
+            json!({ "min": 10, "max": 1 }),
+            json!({ "min": 10, "max": 1, "exclusive": true }),
+        ] {
+            let spec = serde_json::from_value::<SchemaSpec>(json!({
+                "type": "integer",
+                "tests": [{ "name": "between", "params": params }]
+            }))
+            .unwrap();
+            assert_eq!(
+                from_spec(&spec).err(),
+                Some(SpecError::InvalidParams("between.max".into()))
+            );
+        }
+    }
+
+    #[test]
+    fn it_takes_the_absolute_value() {
+        let schema = integer().label("Amount").abs().max(1000);
+        assert_eq!(schema.validate(json!(-250)), Ok(json!(250)));
+        assert_eq!(schema.validate(json!(250)), Ok(json!(250)));
+        assert_eq!(
+            schema.validate(json!(-1001)),
+            Err(json_error(vec![test_error(
+                "max",
+                "",
+                "Amount must be at most 1000."
+            )
+            .with_label("Amount")
+            .with_measure(json!({ "max": 1000 }), json!(1001))]))
+        );
+        assert_eq!(
+            integer().abs().validate(json!(i64::MIN)),
+            Ok(json!(i64::MAX))
+        );
+        let schema = float().abs();
+        assert_eq!(schema.validate(json!(-12.5)), Ok(json!(12.5)));
+        assert_eq!(
+            from_spec(&schema.to_spec().unwrap())
+                .unwrap()
+                .validate(json!("-0.25")),
+            Ok(json!(0.25))
+        );
+    }
+
+    #[test]
+    fn it_negates_numbers() {
+        let schema = integer().negate().positive();
+        assert_eq!(schema.validate(json!(-40)), Ok(json!(40)));
+        assert!(schema.validate(json!(40)).is_err());
+        assert_eq!(
+            integer().negate().validate(json!(i64::MIN)),
+            Ok(json!(i64::MAX))
+        );
+        let schema = from_spec(&float().negate().to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!(1.5)), Ok(json!(-1.5)));
+    }
+
+    #[test]
+    fn it_clamps_numbers() {
+        let schema = unsigned().label("Per page").clamp(1, 100);
+        assert_eq!(schema.validate(json!(0)), Ok(json!(1)));
+        assert_eq!(schema.validate(json!(10000)), Ok(json!(100)));
+        assert_eq!(schema.validate(json!("25")), Ok(json!(25)));
+        let schema = integer().clamp(-10, 10).one_of(vec![-10, 0, 10]);
+        assert_eq!(schema.validate(json!(-50)), Ok(json!(-10)));
+        assert_eq!(schema.validate(json!(50)), Ok(json!(10)));
+        assert!(schema.validate(json!(5)).is_err());
+        let schema = float().clamp(0.0, 1.0);
+        assert_eq!(schema.validate(json!(1.5)), Ok(json!(1.0)));
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(spec.transforms[0].params, json!({ "min": 0.0, "max": 1.0 }));
+        assert_eq!(
+            from_spec(&spec).unwrap().validate(json!(-0.5)),
+            Ok(json!(0.0))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "clamp() requires min <= max")]
+    fn it_rejects_an_inverted_clamp() {
+        unsigned().clamp(100, 1);
+    }
+
+    #[test]
+    fn it_rejects_an_inverted_clamp_in_specs() {
+        let spec = serde_json::from_value::<SchemaSpec>(json!({
+            "type": "float",
+            "transforms": [{ "name": "clamp", "params": { "min": 1.0, "max": 0.0 } }]
+        }))
+        .unwrap();
+        assert_eq!(
+            from_spec(&spec).err(),
+            Some(SpecError::InvalidParams("clamp.max".into()))
+        );
+    }
+
+    #[test]
+    fn it_requires_an_even_number() {
+        let schema = integer().label("Shard").even();
+        for shard in &[0, 2, -4] {
+            assert_eq!(schema.validate(json!(shard)), Ok(json!(shard)));
+        }
+        assert_eq!(
+            schema.validate(json!(-3)),
+            Err(json_error(vec![test_error(
+                "even",
+                "",
+                "Shard must be an even number."
+            )
+            .with_label("Shard")
+            .with_measure(json!(null), json!(-3))]))
+        );
+        let schema = from_spec(&unsigned().even().to_spec().unwrap()).unwrap();
+        assert_eq!(
+            schema.validate(json!(u64::MAX - 1)),
+            Ok(json!(u64::MAX - 1))
+        );
+        assert!(schema.validate(json!(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn it_requires_an_odd_number() {
+        let schema = integer().label("Shard").odd();
+        for shard in &[1, -1, -3] {
+            assert_eq!(schema.validate(json!(shard)), Ok(json!(shard)));
+        }
+        assert_eq!(
+            schema.validate(json!(0)),
+            Err(json_error(vec![test_error(
+                "odd",
+                "",
+                "Shard must be an odd number."
+            )
+            .with_label("Shard")
+            .with_measure(json!(null), json!(0))]))
+        );
+        let schema = unsigned().odd();
+        assert_eq!(schema.validate(json!(7)), Ok(json!(7)));
+        assert!(schema.validate(json!(8)).is_err());
+    }
+
+    #[test]
+    fn it_requires_a_safe_integer() {
+        let schema = integer().label("ID").safe_integer();
+        for id in &[9007199254740991_i64, -9007199254740991] {
+            assert_eq!(schema.validate(json!(id)), Ok(json!(id)));
+        }
+        for id in &[9007199254740992_i64, -9007199254740993] {
+            assert_eq!(
+                schema.validate(json!(id)),
+                Err(json_error(vec![test_error(
+                    "safe_integer",
+                    "",
+                    "ID must be a safe integer, since JavaScript cannot represent integers beyond 2^53 - 1 exactly."
+                )
+                .with_label("ID")
+                .with_measure(json!({ "max": 9007199254740991_i64 }), json!(id))]))
+            );
+        }
+        let schema = from_spec(&unsigned().safe_integer().to_spec().unwrap()).unwrap();
+        assert_eq!(
+            schema.validate(json!(9007199254740991_u64)),
+            Ok(json!(9007199254740991_u64))
+        );
+        assert!(schema.validate(json!(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn it_requires_a_port() {
+        let schema = unsigned().label("Port").port();
+        assert_eq!(schema.validate(json!(1)), Ok(json!(1)));
+        assert_eq!(schema.validate(json!("8080")), Ok(json!(8080)));
+        assert_eq!(schema.validate(json!(65535)), Ok(json!(65535)));
+        for port in &[0, 65536] {
+            assert_eq!(
+                schema.validate(json!(port)),
+                Err(json_error(vec![test_error(
+                    "port",
+                    "",
+                    "Port must be a port number between 1 and 65535."
+                )
+                .with_label("Port")
+                .with_measure(json!({ "min": 1, "max": 65535 }), json!(port))]))
+            );
+        }
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!(0)).is_err());
+    }
+
+    #[test]
+    fn it_requires_a_percentage() {
+        let schema = integer().label("Discount").percent();
+        assert_eq!(schema.validate(json!(0)), Ok(json!(0)));
+        assert_eq!(schema.validate(json!(100)), Ok(json!(100)));
+        assert_eq!(
+            schema.validate(json!(-1)),
+            Err(json_error(vec![test_error(
+                "percent",
+                "",
+                "Discount must be a percentage between 0 and 100."
+            )
+            .with_label("Discount")
+            .with_measure(json!({ "min": 0, "max": 100 }), json!(-1))]))
+        );
+        assert!(schema.validate(json!(101)).is_err());
+    }
+
+    #[test]
+    fn it_requires_coordinates() {
+        let schema = object()
+            .float("lat", |n| n.label("Latitude").latitude())
+            .float("lng", |n| n.label("Longitude").longitude());
+        assert_eq!(
+            schema.validate(json!({ "lat": -90.0, "lng": 180.0 })),
+            Ok(json!({ "lat": -90.0, "lng": 180.0 }))
+        );
+        assert_eq!(
+            schema.validate(json!({ "lat": 90.5, "lng": -180.5 })),
+            Err(json_error(vec![
+                test_error(
+                    "latitude",
+                    "lat",
+                    "Latitude must be a latitude between -90 and 90."
+                )
+                .with_label("Latitude")
+                .with_measure(json!({ "min": -90.0, "max": 90.0 }), json!(90.5)),
+                test_error(
+                    "longitude",
+                    "lng",
+                    "Longitude must be a longitude between -180 and 180."
+                )
+                .with_label("Longitude")
+                .with_measure(json!({ "min": -180.0, "max": 180.0 }), json!(-180.5)),
+            ]))
+        );
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema
+            .validate(json!({ "lat": 0.0, "lng": 181.0 }))
+            .is_err());
+    }
+
+    #[test]
+    fn it_requires_a_sign() {
+        let u = unsigned().label("u64").positive();
+        let i = integer().label("i64").negative();
+        let f = float().label("f64").non_negative();
+        assert_eq!(u.validate(json!(1)), Ok(json!(1)));
+        assert_eq!(i.validate(json!(-1)), Ok(json!(-1)));
+        assert_eq!(f.validate(json!(0.0)), Ok(json!(0.0)));
+        assert_eq!(f.validate(json!(-0.0)), Ok(json!(-0.0)));
+        assert_eq!(
+            u.validate(json!(0)),
+            Err(json_error(vec![test_error(
+                "positive",
+                "",
+                "u64 must be positive."
+            )
+            .with_label("u64")
+            .with_measure(json!(null), json!(0))]))
+        );
+        assert_eq!(
+            i.validate(json!(0)),
+            Err(json_error(vec![test_error(
+                "negative",
+                "",
+                "i64 must be negative."
+            )
+            .with_label("i64")
+            .with_measure(json!(null), json!(0))]))
+        );
+        assert_eq!(
+            f.validate(json!(-0.5)),
+            Err(json_error(vec![test_error(
+                "non_negative",
+                "",
+                "f64 must not be negative."
+            )
+            .with_label("f64")
+            .with_measure(json!(null), json!(-0.5))]))
+        );
+        assert!(unsigned().negative().validate(json!(0)).is_err());
+        assert!(unsigned().negative().validate(json!(1)).is_err());
+        assert!(float().positive().validate(json!(-0.0)).is_err());
+    }
+
+    #[test]
+    fn it_rejects_zero() {
+        let schema = float().label("Divisor").non_zero();
+        assert_eq!(schema.validate(json!(0.5)), Ok(json!(0.5)));
+        for (zero, actual) in &[(json!(0.0), 0.0), (json!(-0.0), -0.0), (json!("-0"), -0.0)] {
+            assert_eq!(
+                schema.validate(zero.clone()),
+                Err(json_error(vec![test_error(
+                    "non_zero",
+                    "",
+                    "Divisor must not be zero."
+                )
+                .with_label("Divisor")
+                .with_measure(json!(null), json!(actual))])),
+                "{}",
+                zero
+            );
+        }
+        let schema = integer().non_zero();
+        assert_eq!(schema.validate(json!(-3)), Ok(json!(-3)));
+        assert!(schema.validate(json!(0)).is_err());
+    }
+
+    #[test]
+    fn it_limits_decimal_places() {
+        let schema = float().label("Price").decimal_places(2);
+        for price in &[
+            json!(12.3),
+            json!(12.30),
+            json!(0.07),
+            json!(-4.99),
+            json!(12.0),
+        ] {
+            assert_eq!(schema.validate(price.clone()), Ok(price.clone()));
+        }
+        for (price, actual) in &[
+            (json!(12.345), 12.345),
+            (json!("12.345"), 12.345),
+            (json!(0.001), 0.001),
+        ] {
+            assert_eq!(
+                schema.validate(price.clone()),
+                Err(json_error(vec![test_error(
+                    "decimal_places",
+                    "",
+                    "Price must have at most 2 decimal places."
+                )
+                .with_label("Price")
+                .with_measure(json!({ "max": 2 }), json!(actual))])),
+                "{}",
+                price
+            );
+        }
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!(1.005)).is_err());
+    }
+
+    #[test]
+    fn it_limits_decimal_places_of_large_floats() {
+        let schema = float().decimal_places(2);
+        for price in &[json!(1234567.89), json!(12345678.9), json!(1e15)] {
+            assert_eq!(schema.validate(price.clone()), Ok(price.clone()));
+        }
+        for price in &[json!(1234567.891), json!(12345678.9012)] {
+            assert!(schema.validate(price.clone()).is_err(), "{}", price);
+        }
+    }
+
+    #[test]
+    fn it_rounds_floats() {
+        assert_eq!(float().round().validate(json!(2.5)), Ok(json!(3.0)));
+        assert_eq!(float().round().validate(json!(-2.5)), Ok(json!(-3.0)));
+        assert_eq!(float().floor().validate(json!(-1.2)), Ok(json!(-2.0)));
+        assert_eq!(float().floor().validate(json!(1.8)), Ok(json!(1.0)));
+        assert_eq!(float().ceil().validate(json!(-1.8)), Ok(json!(-1.0)));
+        assert_eq!(float().ceil().validate(json!("1.2")), Ok(json!(2.0)));
+    }
+
+    #[test]
+    fn it_rounds_floats_to_decimal_places() {
+        let schema = float().round_to(2).decimal_places(2);
+        assert_eq!(schema.validate(json!(12.345678)), Ok(json!(12.35)));
+        assert_eq!(schema.validate(json!(-0.125)), Ok(json!(-0.13)));
+        assert_eq!(schema.validate(json!(1.005)), Ok(json!(1.0)));
+        assert_eq!(schema.validate(json!("0.0149")), Ok(json!(0.01)));
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(spec.transforms[0].params, json!({ "places": 2 }));
+        assert_eq!(
+            from_spec(&spec).unwrap().validate(json!(9.87654)),
+            Ok(json!(9.88))
+        );
+    }
+
+    #[test]
+    fn it_limits_significant_digits() {
+        let schema = float().label("Reading").max_significant_digits(3);
+        for reading in &[json!(123.0), json!(1.23), json!(0.000123), json!(-12300.0)] {
+            assert_eq!(schema.validate(reading.clone()), Ok(reading.clone()));
+        }
+        assert_eq!(
+            schema.validate(json!(1.234)),
+            Err(json_error(vec![test_error(
+                "max_significant_digits",
+                "",
+                "Reading must have at most 3 significant digits."
+            )
+            .with_label("Reading")
+            .with_measure(json!({ "max": 3 }), json!(1.234))]))
+        );
+        let schema = from_spec(&schema.to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!(1234.0)).is_err());
+    }
+
+    #[test]
+    fn it_allows_only_listed_values() {
+        let schema = integer().label("Per page").one_of(vec![10, 25, 50, 100]);
+        assert_eq!(schema.validate(json!(50)), Ok(json!(50)));
+        assert_eq!(schema.validate(json!("25")), Ok(json!(25)));
+        assert_eq!(
+            schema.validate(json!(20)),
+            Err(json_error(vec![test_error(
+                "one_of",
+                "",
+                "Per page must be one of the following: 10, 25, 50, 100"
+            )
+            .with_label("Per page")
+            .with_measure(
+                json!({ "values": [10, 25, 50, 100] }),
+                json!(20)
+            )]))
+        );
+        let schema = float().one_of(vec![0.3, 0.5]);
+        assert_eq!(schema.validate(json!("0.5")), Ok(json!(0.5)));
+        assert!(schema.validate(json!(0.1 + 0.2)).is_err());
+    }
+
+    #[test]
+    fn it_excludes_values() {
+        let schema = integer().label("Lucky Number").not_one_of(vec![4, 13]);
+        assert_eq!(schema.validate(json!(7)), Ok(json!(7)));
+        assert_eq!(
+            to_json(schema.validate(json!(13)).unwrap_err()).unwrap(),
+            json!({
+                "type": "invalid_json",
+                "path": "",
+                "message": "1 validation error occurred.",
+                "errors": [{
+                    "type": "not_one_of",
+                    "path": "",
+                    "label": "Lucky Number",
+                    "message": "Lucky Number must not be one of the following: 4, 13",
+                    "params": { "values": [4, 13], "actual": 13 },
+                    "expected": { "values": [4, 13] },
+                    "actual": 13,
+                    "errors": []
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn it_parses_a_comma_decimal_separator() {
+        let schema = float().decimal_separator(',');
+        assert_eq!(schema.validate(json!("2,75")), Ok(json!(2.75)));
+        assert_eq!(schema.validate(json!("1,234")), Ok(json!(1.234)));
+        assert_eq!(schema.validate(json!(2.75)), Ok(json!(2.75)));
+        ["1.5", "1.234,56"].iter().for_each(|string| {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Float,
+                    Some(&json!(string))
+                )]))
+            );
+        });
+    }
+
+    #[test]
+    fn it_parses_grouped_numbers() {
+        let schema = float().decimal_separator(',').group_separator('.');
+        assert_eq!(schema.validate(json!("1.234,56")), Ok(json!(1234.56)));
+        assert_eq!(schema.validate(json!("-1.234.567")), Ok(json!(-1234567.0)));
+        assert_eq!(schema.validate(json!("1,234")), Ok(json!(1.234)));
+        assert_eq!(schema.validate(json!("1.234")), Ok(json!(1234.0)));
+        ["1.23,4", "12.34", "1234.567,8", ".234"]
+            .iter()
+            .for_each(|string| {
+                assert_eq!(
+                    schema.validate(json!(string)),
+                    Err(json_error(vec![type_error(
+                        "",
+                        "",
+                        JsonType::Float,
+                        Some(&json!(string))
+                    )]))
+                );
+            });
+        let schema = integer().group_separator(',');
+        assert_eq!(schema.validate(json!("1,234")), Ok(json!(1234)));
+        assert_eq!(schema.validate(json!("1234")), Ok(json!(1234)));
+        let schema = unsigned().decimal_separator(',').group_separator('.');
+        assert_eq!(schema.validate(json!("1.234")), Ok(json!(1234)));
+    }
+
+    #[test]
+    fn it_keeps_the_default_decimal_separator() {
+        let schema = float();
+        assert_eq!(schema.validate(json!("1.234")), Ok(json!(1.234)));
+        assert_eq!(
+            schema.validate(json!("1,234")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Float,
+                Some(&json!("1,234"))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_toggles_string_coercion() {
+        let schema = integer().coercions(|c| c.allow_string_to_number(false));
+        assert_eq!(schema.validate(json!(42)), Ok(json!(42)));
+        assert_eq!(schema.validate(json!(42.0)), Ok(json!(42)));
+        assert_eq!(
+            schema.validate(json!("42")),
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!("42"))
             )]))
         );
+        let schema = float()
+            .coercions(|c| c.allow_string_to_number(false))
+            .coercions(|c| c.allow_string_to_number(true));
+        assert_eq!(schema.validate(json!("1.5")), Ok(json!(1.5)));
+    }
+
+    #[test]
+    fn it_rejects_strings_in_strict_mode() {
+        let schema = integer().strict();
+        assert_eq!(schema.validate(json!(1)), Ok(json!(1)));
+        assert_eq!(schema.validate(json!(1.0)), Ok(json!(1)));
+        for input in &[json!("1"), json!("1.0")] {
+            assert_eq!(
+                schema.validate(input.clone()),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Integer,
+                    Some(input)
+                )]))
+            );
+        }
+        let schema = from_spec(&unsigned().strict().to_spec().unwrap()).unwrap();
+        assert!(schema.validate(json!("7")).is_err());
+        assert_eq!(float().strict().validate(json!(1.5)), Ok(json!(1.5)));
+        assert!(float().strict().validate(json!("1.5")).is_err());
+        assert_eq!(integer().validate(json!("1")), Ok(json!(1)));
+    }
+
+    #[test]
+    fn it_parses_scientific_notation_and_underscores_leniently() {
+        let schema = integer().lenient_parse();
+        assert_eq!(schema.validate(json!("1e6")), Ok(json!(1000000)));
+        assert_eq!(schema.validate(json!("1_000_000")), Ok(json!(1000000)));
+        assert_eq!(schema.validate(json!("-2.5E2")), Ok(json!(-250)));
+        assert_eq!(schema.validate(json!("1000e-3")), Ok(json!(1)));
+        assert_eq!(schema.validate(json!("0e-9")), Ok(json!(0)));
+        for string in &[
+            "1.5e0", "1e-3", "1500e-3", "_", "1__000", "_1", "1_", "e6", "1e",
+        ] {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Integer,
+                    Some(&json!(string))
+                )])),
+                "{}",
+                string
+            );
+        }
+        for string in &["1e19", "-1e400"] {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![range_error(
+                    "",
+                    "",
+                    JsonType::Integer,
+                    &json!(string)
+                )])),
+                "{}",
+                string
+            );
+        }
+        let schema = unsigned().lenient_parse();
+        assert_eq!(schema.validate(json!("2_5e1")), Ok(json!(250)));
+        assert!(schema.validate(json!("-1e2")).is_err());
+        let schema = float().lenient_parse();
+        assert_eq!(schema.validate(json!("1.5e0")), Ok(json!(1.5)));
+        assert_eq!(schema.validate(json!("1_000.25")), Ok(json!(1000.25)));
+        let schema = from_spec(&integer().lenient_parse().to_spec().unwrap()).unwrap();
+        assert_eq!(schema.validate(json!("2e3")), Ok(json!(2000)));
+    }
+
+    #[test]
+    fn it_parses_strictly_by_default() {
+        let schema = integer();
+        for string in &["1e6", "1_000"] {
+            assert_eq!(
+                schema.validate(json!(string)),
+                Err(json_error(vec![type_error(
+                    "",
+                    "",
+                    JsonType::Integer,
+                    Some(&json!(string))
+                )]))
+            );
+        }
+        assert!(float().validate(json!("1_000.25")).is_err());
+    }
+
+    #[test]
+    fn it_reports_unrepresentable_output() {
+        let mut schema = float();
+        schema
+            .validator
+            .add_transform("explode", |float| float * f64::INFINITY);
+        let schema = object().key("score", schema);
+        let error = schema.validate(json!({ "score": 1.5 })).unwrap_err();
+        let error = error.flatten().next().unwrap();
+        assert_eq!(error.code(), &ErrorCode::Internal);
+        assert_eq!(error.path(), "score");
+        assert_eq!(
+            error.message(),
+            "An internal error occurred: the value cannot be represented as JSON."
+        );
+    }
+
+    #[test]
+    fn it_runs_custom_tests_alongside_built_in_tests() {
+        let schema = integer()
+            .label("Count")
+            .min(10)
+            .test("even", "<label> must be even.", |n| Ok(n % 2 == 0));
+        assert_eq!(schema.validate(json!(12)), Ok(json!(12)));
+        assert_eq!(
+            schema.validate(json!(7)),
+            Err(json_error(vec![
+                test_error("min", "", "Count must be at least 10.")
+                    .with_label("Count")
+                    .with_measure(json!({ "min": 10 }), json!(7)),
+                test_error("even", "", "Count must be even.").with_label("Count"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn it_reports_dynamic_messages_from_custom_tests() {
+        let schema = object().key(
+            "seats",
+            unsigned()
+                .label("Seats")
+                .test("plan", "<label> is invalid.", |seats| {
+                    Ok(match seats {
+                        1..=5 => TestOutcome::Pass,
+                        seats => TestOutcome::FailWith(format!(
+                            "value {} is not in the allowed set for plan 'basic'",
+                            seats
+                        )),
+                    })
+                }),
+        );
+        assert_eq!(
+            to_json(schema.validate(json!({ "seats": 17 })).unwrap_err()).unwrap(),
+            json!({
+                "type": "invalid_json",
+                "path": "",
+                "message": "1 validation error occurred.",
+                "errors": [{
+                    "type": "plan",
+                    "path": "seats",
+                    "segments": ["seats"],
+                    "label": "Seats",
+                    "message": "value 17 is not in the allowed set for plan 'basic'",
+                    "errors": []
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn it_fills_in_a_default_value() {
+        let schema = object().key("page", integer().optional().default_value(1).min(1));
+        assert_eq!(schema.validate(json!({})), Ok(json!({ "page": 1 })));
+        assert_eq!(
+            schema.validate(json!({ "page": 3 })),
+            Ok(json!({ "page": 3 }))
+        );
+    }
+
+    #[test]
+    fn it_fills_in_defaults_for_each_numeric_type() {
+        let schema = object()
+            .unsigned("page", |n| n.optional().default_value(1))
+            .integer("offset", |n| n.optional().default_value(-5))
+            .float("ratio", |n| n.optional().default_value(0.5).round());
+        assert_eq!(
+            schema.validate(json!({})),
+            Ok(json!({ "page": 1, "offset": -5, "ratio": 1.0 }))
+        );
+        assert_eq!(
+            schema.validate(json!({ "page": 2, "ratio": 0.25 })),
+            Ok(json!({ "page": 2, "offset": -5, "ratio": 0.0 }))
+        );
+        let schema = object().key(
+            "query",
+            object().key("limit", unsigned().optional().default_value(20)),
+        );
+        assert_eq!(
+            schema.validate(json!({ "query": {} })),
+            Ok(json!({ "query": { "limit": 20 } }))
+        );
+    }
+
+    #[test]
+    fn it_tests_the_default_value() {
+        let schema = object().key(
+            "page",
+            integer().label("Page").optional().default_value(0).min(1),
+        );
+        assert_eq!(
+            schema.validate(json!({})),
+            Err(json_error(vec![test_error(
+                "min",
+                "page",
+                "Page must be at least 1."
+            )
+            .with_label("Page")
+            .with_measure(json!({ "min": 1 }), json!(0))]))
+        );
     }
 }
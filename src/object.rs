@@ -1,27 +1,43 @@
 use super::{
     array::ArraySchema,
     boolean::BooleanSchema,
-    error::{ValidationError, ValidationResult},
-    json::{from_json, Json, JsonType, Object},
+    bytes::BytesSchema,
+    context::Context,
+    de::Streaming,
+    error::{PathSegment, ValidationError, ValidationResult},
+    json::{Json, JsonType, Object},
     number::NumberSchema,
+    spec::{from_spec, SchemaSpec, SpecError},
     string::StringSchema,
-    OkSchema, Validator,
+    OkSchema, TestOutcome, Validator,
 };
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct ObjectSchema {
     validator: Validator<Object>,
-    property_schemas: HashMap<String, Box<OkSchema>>,
+    property_schemas: BTreeMap<String, Box<dyn OkSchema>>,
 }
 
 impl ObjectSchema {
     pub fn new() -> Self {
         ObjectSchema {
-            property_schemas: HashMap::new(),
+            property_schemas: BTreeMap::new(),
             validator: Validator::new(JsonType::Object),
         }
     }
 
+    pub fn test<M, F, O>(mut self, name: &'static str, message: M, test: F) -> Self
+    where
+        M: Into<String>,
+        F: Fn(&Object) -> std::result::Result<O, ValidationError> + 'static,
+        O: Into<TestOutcome>,
+    {
+        self.validator
+            .add_test(name, message, Json::Null, test)
+            .custom();
+        self
+    }
+
     pub fn key(mut self, key: &str, schema: impl OkSchema + 'static) -> Self {
         self.property_schemas.insert(key.into(), Box::new(schema));
         self
@@ -31,6 +47,10 @@ impl ObjectSchema {
         self.key(key, build(BooleanSchema::new()))
     }
 
+    pub fn bytes(self, key: &str, build: fn(BytesSchema) -> BytesSchema) -> Self {
+        self.key(key, build(BytesSchema::new()))
+    }
+
     pub fn integer(self, key: &str, build: fn(NumberSchema<i64>) -> NumberSchema<i64>) -> Self {
         self.key(key, build(NumberSchema::new(JsonType::Integer)))
     }
@@ -54,16 +74,30 @@ impl ObjectSchema {
     pub fn array(self, key: &str, build: fn(ArraySchema) -> ArraySchema) -> Self {
         self.key(key, build(ArraySchema::new()))
     }
+
+    pub fn from_spec(spec: &SchemaSpec) -> Result<Self, SpecError> {
+        let mut schema = ObjectSchema::new();
+        schema.validator.load_spec(spec);
+        if let Some(rule) = spec.transforms.iter().chain(spec.tests.iter()).next() {
+            return Err(SpecError::UnknownRule(rule.name.clone()));
+        }
+        for (key, property) in spec.properties.iter() {
+            schema
+                .property_schemas
+                .insert(key.clone(), from_spec(property)?);
+        }
+        Ok(schema)
+    }
 }
 
 impl OkSchema for ObjectSchema {
     fn label(mut self, label: &'static str) -> Self {
-        self.validator.label = Some(label);
+        self.validator.label = Some(label.into());
         self
     }
 
     fn desc(mut self, description: &'static str) -> Self {
-        self.validator.description = Some(description);
+        self.validator.description = Some(description.into());
         self
     }
 
@@ -77,31 +111,44 @@ impl OkSchema for ObjectSchema {
         self
     }
 
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
     fn validate_at(
         &self,
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>> {
         let mut errors = vec![];
-        let validated = self.validator.exec(path, value, &mut errors);
+        let validated = self.validator.exec(path, value, &mut errors, context);
         let mut fields = match validated {
-            Ok(None) => return Ok(None),
-            Ok(Some(json)) => {
-                if self.property_schemas.is_empty() {
-                    return Ok(Some(json));
-                }
-                from_json::<Object>(json).unwrap()
+            Ok(Some(Json::Object(fields))) if !self.property_schemas.is_empty() => fields,
+            Ok(value) => return Ok(value),
+            Err(_) => {
+                all_errors.append(&mut errors);
+                return Err(());
             }
-            Err(_) => return Err(all_errors.append(&mut errors)),
         };
         let mut object = Object::new();
-        self.property_schemas.iter().for_each(|(key, schema)| {
+        for (key, schema) in self.property_schemas.iter() {
             let path = match path {
                 "" => key.to_string(),
                 path => format!("{}.{}", path, key),
             };
-            match schema.validate_at(path.as_str(), fields.remove(key), &mut errors) {
+            if context.exhausted(&path, &mut errors) {
+                break;
+            }
+            context.push(PathSegment::Key(key.to_string()));
+            match schema.validate_at(path.as_str(), fields.remove(key), &mut errors, context) {
                 Ok(None) | Err(_) => (),
                 Ok(Some(value)) => {
                     if errors.is_empty() {
@@ -109,11 +156,35 @@ impl OkSchema for ObjectSchema {
                     }
                 }
             };
-        });
+            context.pop();
+        }
         if errors.is_empty() {
             return Ok(Some(object.into()));
         }
-        Err(all_errors.append(&mut errors))
+        all_errors.extend(errors.into_iter().map(|error| self.validator.redact(error)));
+        Err(())
+    }
+
+    fn streaming(&self) -> Streaming<'_> {
+        let validator = &self.validator;
+        if self.property_schemas.is_empty()
+            || !validator.tests.is_empty()
+            || !validator.transforms.is_empty()
+        {
+            return Streaming::Buffered;
+        }
+        Streaming::Properties {
+            schemas: &self.property_schemas,
+            sensitive: validator.is_sensitive,
+        }
+    }
+
+    fn to_spec(&self) -> Result<SchemaSpec, SpecError> {
+        let mut spec = self.validator.to_spec()?;
+        for (key, schema) in self.property_schemas.iter() {
+            spec.properties.insert(key.clone(), schema.to_spec()?);
+        }
+        Ok(spec)
     }
 }
 
@@ -124,9 +195,9 @@ pub fn object() -> ObjectSchema {
 #[cfg(test)]
 mod tests {
     use super::super::{
-        error::{json_error, type_error},
+        error::{json_error, test_error, type_error},
         json::JsonType,
-        object, OkSchema,
+        object, OkSchema, PathSegment, TestOutcome,
     };
     use serde_json::json;
 
@@ -136,23 +207,48 @@ mod tests {
         assert_eq!(schema.validate(json!({})), Ok(json!({})));
         assert_eq!(
             schema.validate(json!(null)),
-            Err(json_error(vec![type_error("", "", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Object,
+                Some(&json!(null))
+            )]))
         );
         assert_eq!(
             schema.validate(json!([])),
-            Err(json_error(vec![type_error("", "", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Object,
+                Some(&json!([]))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(1)),
-            Err(json_error(vec![type_error("", "", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Object,
+                Some(&json!(1))
+            )]))
         );
         assert_eq!(
             schema.validate(json!(true)),
-            Err(json_error(vec![type_error("", "", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Object,
+                Some(&json!(true))
+            )]))
         );
         assert_eq!(
             schema.validate(json!("foo")),
-            Err(json_error(vec![type_error("", "", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Object,
+                Some(&json!("foo"))
+            )]))
         );
     }
 
@@ -168,8 +264,9 @@ mod tests {
             Err(json_error(vec![type_error(
                 "foo",
                 "foo",
-                JsonType::Boolean
-            ),]))
+                JsonType::Boolean,
+                Some(&json!("bar"))
+            )]))
         );
     }
 
@@ -185,7 +282,8 @@ mod tests {
             Err(json_error(vec![type_error(
                 "foo",
                 "foo",
-                JsonType::Integer
+                JsonType::Integer,
+                Some(&json!(""))
             )]))
         );
 
@@ -196,7 +294,12 @@ mod tests {
         );
         assert_eq!(
             schema.validate(json!({ "foo": "" })),
-            Err(json_error(vec![type_error("foo", "foo", JsonType::Float)]))
+            Err(json_error(vec![type_error(
+                "foo",
+                "foo",
+                JsonType::Float,
+                Some(&json!(""))
+            )]))
         );
 
         let schema = object().unsigned("foo", |field| field.desc("An unsigned."));
@@ -209,7 +312,8 @@ mod tests {
             Err(json_error(vec![type_error(
                 "foo",
                 "foo",
-                JsonType::Unsigned
+                JsonType::Unsigned,
+                Some(&json!(""))
             )]))
         );
     }
@@ -223,9 +327,12 @@ mod tests {
         );
         assert_eq!(
             schema.validate(json!({ "foo": null })),
-            Err(json_error(
-                vec![type_error("foo", "foo", JsonType::String),]
-            ))
+            Err(json_error(vec![type_error(
+                "foo",
+                "foo",
+                JsonType::String,
+                Some(&json!(null))
+            )]))
         );
     }
 
@@ -238,7 +345,12 @@ mod tests {
         );
         assert_eq!(
             schema.validate(json!({ "foo": true })),
-            Err(json_error(vec![type_error("foo", "foo", JsonType::Object)]))
+            Err(json_error(vec![type_error(
+                "foo",
+                "foo",
+                JsonType::Object,
+                Some(&json!(true))
+            )]))
         );
     }
 
@@ -251,7 +363,208 @@ mod tests {
         );
         assert_eq!(
             schema.validate(json!({ "foo": true })),
-            Err(json_error(vec![type_error("foo", "foo", JsonType::Array)]))
+            Err(json_error(vec![type_error(
+                "foo",
+                "foo",
+                JsonType::Array,
+                Some(&json!(true))
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_reports_nested_errors() {
+        let schema = object()
+            .string("name", |name| name.min_length(1))
+            .object("address", |address| {
+                address
+                    .string("street", |street| street)
+                    .unsigned("zip", |zip| zip.max(99999))
+            })
+            .integer("age", |age| age.min(0));
+        let error = schema
+            .validate(json!({
+                "name": "",
+                "address": { "zip": 100000 },
+                "age": -1
+            }))
+            .unwrap_err();
+        assert_eq!(
+            error.to_report(),
+            concat!(
+                "✗ 4 validation errors occurred.\n",
+                "  ✗ address.street — address.street must be of type `String`, but received nothing.\n",
+                "  ✗ address.zip — address.zip must be at most 99999.\n",
+                "  ✗ age — age must be at least 0.\n",
+                "  ✗ name — name must be at least 1 characters long.\n",
+            )
+        );
+    }
+
+    #[test]
+    fn it_labels_errors_separately_from_messages() {
+        let schema = object()
+            .string("email", |email| email.label("Email Address").min_length(3))
+            .object("address", |address| {
+                address.unsigned("zip", |zip| zip.max(99999))
+            });
+        let error = schema
+            .validate(json!({ "email": "a", "address": { "zip": 100000 } }))
+            .unwrap_err();
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| (error.label(), error.message()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("zip", "address.zip must be at most 99999."),
+                (
+                    "Email Address",
+                    "Email Address must be at least 3 characters long."
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_redacts_sensitive_values_from_errors() {
+        let schema = object()
+            .integer("pin", |pin| pin.sensitive())
+            .integer("code", |code| code.sensitive().min(1000))
+            .integer("age", |age| age);
+        let error = schema
+            .validate(json!({ "pin": "hunter2", "code": 42, "age": "abc" }))
+            .unwrap_err();
+        let serialized = serde_json::to_string(&error).unwrap();
+        assert!(!serialized.contains("hunter2"));
+        assert!(!serialized.contains("42"));
+        assert!(serialized.contains(r#"String(\"abc\")"#));
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| error.message())
+                .collect::<Vec<_>>(),
+            vec![
+                "age must be of type `Integer`, but received String(\"abc\").",
+                "code must be at least 1000.",
+                "pin must be of type `Integer`, but received [redacted].",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_redacts_dynamic_messages_of_sensitive_fields() {
+        let schema = object()
+            .string("password", |password| {
+                password
+                    .sensitive()
+                    .test("strength", "<label> is too weak.", |password| {
+                        Ok(match password.len() >= 8 {
+                            true => TestOutcome::Pass,
+                            false => TestOutcome::FailWith(format!(
+                                "<label> `{}` is too weak.",
+                                password
+                            )),
+                        })
+                    })
+            })
+            .string("username", |username| {
+                username.test("reserved", "<label> is reserved.", |username| {
+                    Ok(match username.as_str() {
+                        "admin" => {
+                            TestOutcome::FailWith(format!("<label> `{}` is reserved.", username))
+                        }
+                        _ => TestOutcome::Pass,
+                    })
+                })
+            });
+        let error = schema
+            .validate(json!({ "password": "hunter2", "username": "admin" }))
+            .unwrap_err();
+        assert!(!serde_json::to_string(&error).unwrap().contains("hunter2"));
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| error.message())
+                .collect::<Vec<_>>(),
+            vec!["password is too weak.", "username `admin` is reserved."]
+        );
+    }
+
+    #[test]
+    fn it_records_typed_path_segments() {
+        let schema = object()
+            .array("items", |items| {
+                items.of(object().float("unit.price", |price| price.min(0.0)))
+            })
+            .object("meta", |meta| meta.string("v1.0", |version| version));
+        let error = schema
+            .validate(json!({
+                "items": [{ "unit.price": 1.0 }, { "unit.price": -1.0 }],
+                "meta": {}
+            }))
+            .unwrap_err();
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| error.segments().to_vec())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![
+                    PathSegment::Key("items".into()),
+                    PathSegment::Index(1),
+                    PathSegment::Key("unit.price".into()),
+                ],
+                vec![
+                    PathSegment::Key("meta".into()),
+                    PathSegment::Key("v1.0".into()),
+                ],
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(error.flatten().next().unwrap().segments()).unwrap(),
+            json!(["items", 1, "unit.price"])
+        );
+    }
+
+    #[test]
+    fn it_labels_keys_containing_dots() {
+        let schema = object().object("meta", |meta| {
+            meta.string("v1.0", |version| version)
+                .string("v2.0", |version| version.label("Version"))
+        });
+        let error = schema.validate(json!({ "meta": {} })).unwrap_err();
+        assert_eq!(
+            error
+                .flatten()
+                .map(|error| (error.path(), error.label()))
+                .collect::<Vec<_>>(),
+            vec![("meta.v1.0", "v1.0"), ("meta.v2.0", "Version")]
+        );
+    }
+
+    #[test]
+    fn it_runs_custom_tests() {
+        let schema = object()
+            .label("Signup")
+            .test(
+                "passwords_match",
+                "<label> passwords must match.",
+                |fields| Ok(fields.get("password") == fields.get("confirm")),
+            )
+            .string("password", |password| password.min_length(8));
+        assert_eq!(
+            schema.validate(json!({ "password": "hunter22", "confirm": "hunter22" })),
+            Ok(json!({ "password": "hunter22" }))
+        );
+        assert_eq!(
+            schema.validate(json!({ "password": "hunter22", "confirm": "hunter2" })),
+            Err(json_error(vec![test_error(
+                "passwords_match",
+                "",
+                "Signup passwords must match."
+            )
+            .with_label("Signup")]))
         );
     }
 }
@@ -0,0 +1,249 @@
+use super::{
+    context::Context,
+    error::{json_error, reference_error, Result, ValidationError, ValidationResult},
+    json::{Json, JsonType},
+    spec::{SchemaSpec, SpecError},
+    OkSchema, Validator,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+type Schemas = RefCell<HashMap<String, Box<dyn OkSchema>>>;
+
+pub struct SchemaRegistry {
+    schemas: Rc<Schemas>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        SchemaRegistry {
+            schemas: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    pub fn register(&mut self, name: &str, schema: impl OkSchema + 'static) -> &mut Self {
+        self.schemas
+            .borrow_mut()
+            .insert(name.into(), Box::new(schema));
+        self
+    }
+
+    pub fn reference(&self, name: &str) -> ReferenceSchema {
+        ReferenceSchema {
+            validator: Validator::new(JsonType::None),
+            name: name.into(),
+            schemas: Rc::downgrade(&self.schemas),
+        }
+    }
+
+    pub fn validate(&self, name: &str, json: Json) -> Result<Json> {
+        match self.schemas.borrow().get(name) {
+            Some(schema) => schema.validate(json),
+            None => Err(json_error(vec![reference_error("", "", name)])),
+        }
+    }
+}
+
+impl Default for SchemaRegistry {
+    fn default() -> Self {
+        SchemaRegistry::new()
+    }
+}
+
+pub struct ReferenceSchema {
+    validator: Validator<Json>,
+    name: String,
+    schemas: Weak<Schemas>,
+}
+
+impl OkSchema for ReferenceSchema {
+    fn label(mut self, label: &'static str) -> Self {
+        self.validator.label = Some(label.into());
+        self
+    }
+
+    fn desc(mut self, description: &'static str) -> Self {
+        self.validator.description = Some(description.into());
+        self
+    }
+
+    fn optional(mut self) -> Self {
+        self.validator.is_optional = true;
+        self
+    }
+
+    fn nullable(mut self) -> Self {
+        self.validator.is_nullable = true;
+        self
+    }
+
+    fn sensitive(mut self) -> Self {
+        self.validator.is_sensitive = true;
+        self
+    }
+
+    fn message(mut self, message: &str) -> Self {
+        self.validator.override_message(message);
+        self
+    }
+
+    fn validate_at(
+        &self,
+        path: &str,
+        value: Option<Json>,
+        all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
+    ) -> ValidationResult<Option<Json>> {
+        match value {
+            None if self.validator.is_optional => return Ok(None),
+            Some(Json::Null) if self.validator.is_nullable => return Ok(value),
+            _ => (),
+        };
+        if let Some(schemas) = self.schemas.upgrade() {
+            if let Some(schema) = schemas.borrow().get(&self.name) {
+                if !context.resolve(&self.name) {
+                    let label = self.validator.label.as_deref().unwrap_or(path);
+                    let error = reference_error(path, label, &self.name);
+                    all_errors.push(error.with_segments(context.segments().to_vec()));
+                    return Err(());
+                }
+                let mut errors = vec![];
+                let validated = schema.validate_at(path, value, &mut errors, context);
+                context.resolved();
+                all_errors.extend(errors.into_iter().map(|error| self.validator.redact(error)));
+                return validated;
+            }
+        }
+        let label = self.validator.label.as_deref().unwrap_or(path);
+        let error = reference_error(path, label, &self.name);
+        all_errors.push(error.with_segments(context.segments().to_vec()));
+        Err(())
+    }
+
+    fn to_spec(&self) -> std::result::Result<SchemaSpec, SpecError> {
+        Err(SpecError::Unserializable(format!(
+            "reference to `{}`",
+            self.name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        array,
+        error::{json_error, reference_error, test_error, type_error},
+        json::JsonType,
+        object, OkSchema, SchemaRegistry,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn it_validates_shared_references() {
+        let mut registry = SchemaRegistry::new();
+        let user = object()
+            .string("username", |username| username)
+            .key("address", registry.reference("Address"));
+        let company = object().key("headquarters", registry.reference("Address"));
+        registry
+            .register("CreateUser", user)
+            .register("CreateCompany", company)
+            .register(
+                "Address",
+                object().string("city", |city| city.min_length(1)),
+            );
+        assert_eq!(
+            registry.validate(
+                "CreateUser",
+                json!({ "username": "foo", "address": { "city": "Anytown" } })
+            ),
+            Ok(json!({ "username": "foo", "address": { "city": "Anytown" } }))
+        );
+        assert_eq!(
+            registry.validate("CreateCompany", json!({ "headquarters": { "city": "" } })),
+            Err(json_error(vec![test_error(
+                "min_length",
+                "headquarters.city",
+                "headquarters.city must be at least 1 characters long."
+            )
+            .with_measure(json!({ "min": 1 }), json!(0))]))
+        );
+    }
+
+    #[test]
+    fn it_validates_recursive_references() {
+        let mut registry = SchemaRegistry::new();
+        let node = object().string("name", |name| name).key(
+            "children",
+            array().of(registry.reference("Node")).optional(),
+        );
+        registry.register("Node", node);
+        assert_eq!(
+            registry.validate(
+                "Node",
+                json!({ "name": "root", "children": [{ "name": "leaf" }] })
+            ),
+            Ok(json!({ "name": "root", "children": [{ "name": "leaf" }] }))
+        );
+        assert_eq!(
+            registry.validate(
+                "Node",
+                json!({ "name": "root", "children": [{ "children": [] }] })
+            ),
+            Err(json_error(vec![type_error(
+                "children[0].name",
+                "children[0].name",
+                JsonType::String,
+                None
+            )]))
+        );
+    }
+
+    #[test]
+    fn it_fails_unresolved_references() {
+        let mut registry = SchemaRegistry::new();
+        let user = object().key("address", registry.reference("Address"));
+        registry.register("User", user);
+        assert_eq!(
+            registry.validate("User", json!({ "address": {} })),
+            Err(json_error(vec![reference_error(
+                "address", "address", "Address"
+            )]))
+        );
+        assert_eq!(
+            registry.validate("Company", json!({})),
+            Err(json_error(vec![reference_error("", "", "Company")]))
+        );
+    }
+
+    #[test]
+    fn it_fails_reference_cycles() {
+        let mut registry = SchemaRegistry::new();
+        let a = registry.reference("A");
+        let b = registry.reference("B");
+        registry.register("A", b).register("B", a);
+        assert_eq!(
+            registry.validate("A", json!({})),
+            Err(json_error(vec![reference_error("", "", "B")]))
+        );
+        let mut registry = SchemaRegistry::new();
+        let node = registry.reference("Node");
+        registry.register("Node", object().key("child", node.optional()));
+        assert_eq!(
+            registry.validate("Node", json!({ "child": { "child": {} } })),
+            Ok(json!({ "child": { "child": {} } }))
+        );
+    }
+
+    #[test]
+    fn it_fails_references_that_outlive_the_registry() {
+        let schema = SchemaRegistry::new().reference("Address").label("Address");
+        assert_eq!(
+            schema.validate(json!({})),
+            Err(json_error(vec![reference_error("", "Address", "Address")]))
+        );
+    }
+}
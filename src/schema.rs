@@ -1,7 +1,13 @@
 use super::{
+    context::{Context, Options, PathFormat, Validated, ValidationObserver},
+    de::Streaming,
     error::{json_error, Result, ValidationError, ValidationResult},
     json::Json,
+    ndjson::NdjsonLines,
+    source,
+    spec::{SchemaSpec, SpecError},
 };
+use std::io::BufRead;
 
 pub trait OkSchema {
     fn label(self, label: &'static str) -> Self
@@ -20,18 +26,91 @@ pub trait OkSchema {
     where
         Self: Sized;
 
+    fn sensitive(self) -> Self
+    where
+        Self: Sized;
+
+    fn message(self, message: &str) -> Self
+    where
+        Self: Sized;
+
+    #[allow(clippy::result_unit_err)]
     fn validate_at(
         &self,
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>>;
 
+    fn to_spec(&self) -> std::result::Result<SchemaSpec, SpecError>;
+
+    fn streaming(&self) -> Streaming<'_> {
+        Streaming::Buffered
+    }
+
     fn validate(&self, json: Json) -> Result<Json> {
         let mut errors = vec![];
-        match self.validate_at("", Some(json), &mut errors) {
-            Ok(value) => Ok(value.unwrap()),
+        match self.validate_at("", Some(json), &mut errors, &mut Context::new()) {
+            Ok(value) => Ok(value.unwrap_or(Json::Null)),
             Err(_) => Err(json_error(errors)),
         }
     }
+
+    fn validate_with_observer(
+        &self,
+        json: Json,
+        observer: &mut dyn ValidationObserver,
+    ) -> Result<Json> {
+        let mut errors = vec![];
+        let mut context = Context::with_observer(observer);
+        match self.validate_at("", Some(json), &mut errors, &mut context) {
+            Ok(value) => Ok(value.unwrap_or(Json::Null)),
+            Err(_) => Err(json_error(errors)),
+        }
+    }
+
+    fn validate_with(&self, json: Json, options: Options) -> Result<Validated> {
+        let mut errors = vec![];
+        let mut context = Context::with_options(&options);
+        match self.validate_at("", Some(json), &mut errors, &mut context) {
+            Ok(value) => Ok(Validated {
+                value: value.unwrap_or(Json::Null),
+                coercions: context.into_coercions(),
+            }),
+            Err(_) if options.path_format == PathFormat::JsonPointer => {
+                Err(json_error(errors).with_json_pointer_paths())
+            }
+            Err(_) => Err(json_error(errors)),
+        }
+    }
+
+    fn validate_str(&self, text: &str) -> Result<Json> {
+        self.validate(source::parse(text, false)?)
+            .map_err(|error| error.with_locations(&source::locate(text)))
+    }
+
+    fn validate_str_with(&self, text: &str, options: Options) -> Result<Validated> {
+        let path_format = options.path_format;
+        let options = Options {
+            path_format: PathFormat::Dotted,
+            ..options
+        };
+        source::parse(text, options.deny_duplicate_keys)
+            .and_then(|json| self.validate_with(json, options))
+            .map_err(|error| {
+                let error = error.with_locations(&source::locate(text));
+                match path_format {
+                    PathFormat::JsonPointer => error.with_json_pointer_paths(),
+                    PathFormat::Dotted => error,
+                }
+            })
+    }
+
+    fn validate_ndjson<R: BufRead>(&self, reader: R) -> NdjsonLines<'_, Self, R>
+    where
+        Self: Sized,
+    {
+        NdjsonLines::new(self, reader)
+    }
 }
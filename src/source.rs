@@ -0,0 +1,300 @@
+use super::error::{
+    duplicate_key_error, json_error, json_pointer, syntax_error, PathSegment, ValidationError,
+};
+use super::json::Json;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub fn parse(text: &str, deny_duplicate_keys: bool) -> Result<Json, ValidationError> {
+    let json = serde_json::from_str(text).map_err(|err| syntax_error(err.to_string()))?;
+    if deny_duplicate_keys {
+        let duplicates = Locator::scan(text).duplicates;
+        if !duplicates.is_empty() {
+            return Err(json_error(duplicates));
+        }
+    }
+    Ok(json)
+}
+
+pub fn locate(text: &str) -> BTreeMap<String, (usize, usize)> {
+    Locator::scan(text).locations
+}
+
+struct Locator<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    line: usize,
+    column: usize,
+    segments: Vec<PathSegment>,
+    locations: BTreeMap<String, (usize, usize)>,
+    duplicates: Vec<ValidationError>,
+}
+
+impl Locator<'_> {
+    fn scan(text: &str) -> Locator<'_> {
+        let mut locator = Locator {
+            bytes: text.as_bytes(),
+            offset: 0,
+            line: 1,
+            column: 1,
+            segments: vec![],
+            locations: BTreeMap::new(),
+            duplicates: vec![],
+        };
+        locator.value();
+        locator
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    fn advance(&mut self) {
+        match self.peek() {
+            Some(b'\n') => {
+                self.line += 1;
+                self.column = 1;
+            }
+            Some(byte) if byte & 0xC0 != 0x80 => self.column += 1,
+            _ => (),
+        }
+        self.offset += 1;
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.advance();
+        }
+    }
+
+    fn value(&mut self) {
+        self.skip_whitespace();
+        self.locations
+            .insert(json_pointer(&self.segments), (self.line, self.column));
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => {
+                self.string();
+            }
+            _ => {
+                while let Some(byte) = self.peek() {
+                    if matches!(byte, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r') {
+                        break;
+                    }
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let start = self.offset;
+        self.advance();
+        while let Some(byte) = self.peek() {
+            self.advance();
+            match byte {
+                b'\\' => self.advance(),
+                b'"' => break,
+                _ => (),
+            }
+        }
+        let raw = &self.bytes[start..self.offset.min(self.bytes.len())];
+        serde_json::from_slice(raw).unwrap_or_default()
+    }
+
+    fn object(&mut self) {
+        self.advance();
+        let mut keys = BTreeSet::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => (),
+                Some(b'}') | None => return self.advance(),
+                Some(_) => {
+                    self.advance();
+                    continue;
+                }
+            }
+            let key = self.string();
+            self.skip_whitespace();
+            if self.peek() == Some(b':') {
+                self.advance();
+            }
+            self.segments.push(PathSegment::Key(key.clone()));
+            self.value();
+            if !keys.insert(key.clone()) {
+                let (line, column) = self.locations[&json_pointer(&self.segments)];
+                let error = duplicate_key_error(self.segments.clone(), &key);
+                self.duplicates.push(error.with_location(line, column));
+            }
+            self.segments.pop();
+        }
+    }
+
+    fn array(&mut self) {
+        self.advance();
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') | None => return self.advance(),
+                Some(b',') => {
+                    self.advance();
+                    continue;
+                }
+                Some(_) => (),
+            }
+            let start = self.offset;
+            self.segments.push(PathSegment::Index(index));
+            self.value();
+            self.segments.pop();
+            if self.offset == start {
+                self.advance();
+            }
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        error::{duplicate_key_error, json_error, type_error},
+        integer,
+        json::JsonType,
+        object, string, OkSchema, Options, PathSegment,
+    };
+    use serde_json::json;
+
+    fn key(key: &str) -> PathSegment {
+        PathSegment::Key(key.into())
+    }
+
+    fn options() -> Options {
+        Options {
+            deny_duplicate_keys: true,
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_last_duplicate_by_default() {
+        let schema = object().string("role", |role| role);
+        assert_eq!(
+            schema.validate_str(r#"{ "role": "user", "role": "admin" }"#),
+            Ok(json!({ "role": "admin" }))
+        );
+    }
+
+    #[test]
+    fn it_denies_duplicate_keys_at_the_root() {
+        let schema = object().string("role", |role| role);
+        assert_eq!(
+            schema
+                .validate_str_with(r#"{ "role": "user", "role": "admin" }"#, options())
+                .map(|validated| validated.value),
+            Err(json_error(vec![duplicate_key_error(
+                vec![key("role")],
+                "role"
+            )
+            .with_location(1, 27)]))
+        );
+        let error = object()
+            .validate_str_with(r#"{ "a.b": 1, "a.b": 2 }"#, options())
+            .unwrap_err();
+        let error = error.flatten().next().unwrap();
+        assert_eq!(error.segments(), &[key("a.b")]);
+        assert_eq!((error.path(), error.label()), ("a.b", "a.b"));
+        assert_eq!((error.line(), error.column()), (Some(1), Some(20)));
+    }
+
+    #[test]
+    fn it_denies_nested_duplicate_keys() {
+        let schema = object()
+            .object("user", |user| user.string("role", |role| role))
+            .array("items", |items| items.of(object().integer("id", |id| id)));
+        let text = r#"{
+            "user": { "role": "user", "role": "admin" },
+            "items": [{ "id": 1 }, { "id": 2, "id": 3, "id": 4 }]
+        }"#;
+        let items_id = vec![key("items"), PathSegment::Index(1), key("id")];
+        assert_eq!(
+            schema
+                .validate_str_with(text, options())
+                .map(|validated| validated.value),
+            Err(json_error(vec![
+                duplicate_key_error(vec![key("user"), key("role")], "role").with_location(2, 47),
+                duplicate_key_error(items_id.clone(), "id").with_location(3, 53),
+                duplicate_key_error(items_id, "id").with_location(3, 62),
+            ]))
+        );
+    }
+
+    #[test]
+    fn it_allows_the_same_key_in_sibling_objects() {
+        let schema = object()
+            .object("a", |a| a.integer("id", |id| id))
+            .object("b", |b| b.integer("id", |id| id));
+        let text = r#"{ "a": { "id": 1 }, "b": { "id": 2 } }"#;
+        assert_eq!(
+            schema
+                .validate_str_with(text, options())
+                .map(|validated| validated.value),
+            Ok(json!({ "a": { "id": 1 }, "b": { "id": 2 } }))
+        );
+        let schema = integer();
+        assert_eq!(
+            schema
+                .validate_str_with("[1, 2", options())
+                .map_err(|error| error.code().as_str().to_string()),
+            Err("invalid_json".to_string())
+        );
+        assert!(string().validate_str("\"foo\"").is_ok());
+    }
+
+    const FIXTURE: &str = r#"{
+  "customer": { "name": "Ada" },
+  "items": [
+    { "sku": "a-1", "price": 10 },
+    { "sku": "b-2", "price": "free" }
+  ]
+}"#;
+
+    #[test]
+    fn it_locates_nested_errors() {
+        let schema = object()
+            .object("customer", |customer| customer.string("name", |name| name))
+            .array("items", |items| {
+                items.of(object()
+                    .string("sku", |sku| sku)
+                    .integer("price", |price| price))
+            });
+        let error = schema.validate_str(FIXTURE).unwrap_err();
+        let error = error.flatten().next().unwrap();
+        assert_eq!(error.path(), "items[1].price");
+        assert_eq!((error.line(), error.column()), (Some(5), Some(30)));
+        let serialized = serde_json::to_value(error).unwrap();
+        assert_eq!(
+            (&serialized["line"], &serialized["column"]),
+            (&json!(5), &json!(30))
+        );
+    }
+
+    #[test]
+    fn it_locates_root_errors() {
+        let error = integer().validate_str("\n\n  [1, 2]").unwrap_err();
+        assert_eq!(
+            error,
+            json_error(vec![type_error(
+                "",
+                "",
+                JsonType::Integer,
+                Some(&json!([1, 2]))
+            )
+            .with_location(3, 3)])
+        );
+        let error = integer().validate(json!([])).unwrap_err();
+        let serialized = serde_json::to_value(&error).unwrap();
+        assert_eq!(serialized["errors"][0].get("line"), None);
+    }
+}
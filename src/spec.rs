@@ -0,0 +1,303 @@
+use super::{
+    array::ArraySchema,
+    boolean::BooleanSchema,
+    bytes::BytesSchema,
+    json::{from_json, Coercions, Json, JsonType},
+    number::NumberSchema,
+    object::ObjectSchema,
+    string::StringSchema,
+    OkSchema,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaKind {
+    Array,
+    Boolean,
+    Bytes,
+    Float,
+    Integer,
+    Object,
+    String,
+    Unsigned,
+}
+
+impl SchemaKind {
+    pub fn from_json_type(json_type: JsonType) -> Option<SchemaKind> {
+        match json_type {
+            JsonType::Array => Some(SchemaKind::Array),
+            JsonType::Boolean => Some(SchemaKind::Boolean),
+            JsonType::Bytes => Some(SchemaKind::Bytes),
+            JsonType::Float => Some(SchemaKind::Float),
+            JsonType::Integer => Some(SchemaKind::Integer),
+            JsonType::Object => Some(SchemaKind::Object),
+            JsonType::String => Some(SchemaKind::String),
+            JsonType::Unsigned => Some(SchemaKind::Unsigned),
+            _ => None,
+        }
+    }
+
+    pub fn json_type(self) -> JsonType {
+        match self {
+            SchemaKind::Array => JsonType::Array,
+            SchemaKind::Boolean => JsonType::Boolean,
+            SchemaKind::Bytes => JsonType::Bytes,
+            SchemaKind::Float => JsonType::Float,
+            SchemaKind::Integer => JsonType::Integer,
+            SchemaKind::Object => JsonType::Object,
+            SchemaKind::String => JsonType::String,
+            SchemaKind::Unsigned => JsonType::Unsigned,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuleSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Json::is_null")]
+    pub params: Json,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl RuleSpec {
+    pub fn param<P: DeserializeOwned>(&self, key: &str) -> Result<P, SpecError> {
+        self.params
+            .get(key)
+            .cloned()
+            .and_then(|param| from_json(param).ok())
+            .ok_or_else(|| SpecError::InvalidParams(format!("{}.{}", self.name, key)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SchemaSpec {
+    #[serde(rename = "type")]
+    pub kind: SchemaKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub nullable: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Json>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coercions: Option<Coercions>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<RuleSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<RuleSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elements: Option<Box<SchemaSpec>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, SchemaSpec>,
+}
+
+fn is_false(flag: &bool) -> bool {
+    !flag
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SpecError {
+    Unserializable(String),
+    UnknownRule(String),
+    InvalidParams(String),
+}
+
+impl std::fmt::Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            SpecError::Unserializable(what) => write!(f, "{} cannot be serialized.", what),
+            SpecError::UnknownRule(name) => write!(f, "Unknown rule `{}`.", name),
+            SpecError::InvalidParams(param) => write!(f, "Invalid parameter `{}`.", param),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+pub fn from_spec(spec: &SchemaSpec) -> Result<Box<dyn OkSchema>, SpecError> {
+    Ok(match spec.kind {
+        SchemaKind::Array => Box::new(ArraySchema::from_spec(spec)?),
+        SchemaKind::Boolean => Box::new(BooleanSchema::from_spec(spec)?),
+        SchemaKind::Bytes => Box::new(BytesSchema::from_spec(spec)?),
+        SchemaKind::Float => Box::new(NumberSchema::<f64>::from_spec(spec)?),
+        SchemaKind::Integer => Box::new(NumberSchema::<i64>::from_spec(spec)?),
+        SchemaKind::Object => Box::new(ObjectSchema::from_spec(spec)?),
+        SchemaKind::String => Box::new(StringSchema::from_spec(spec)?),
+        SchemaKind::Unsigned => Box::new(NumberSchema::<u64>::from_spec(spec)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        array, float, from_spec, integer, object, string, OkSchema, SchemaRegistry, SchemaSpec,
+        SpecError,
+    };
+    use serde_json::{self, json};
+
+    #[test]
+    fn it_round_trips_nested_schemas() {
+        let schema = object()
+            .string("username", |username| {
+                username.label("Username").trim().lowercase().length((1, 8))
+            })
+            .float("rating", |rating| rating.min(0.0).max(5.0).optional())
+            .array("tags", |tags| {
+                tags.max_length(2)
+                    .of(string().matches("^[a-z]+$").desc("A tag.").sensitive())
+                    .nullable()
+            })
+            .key(
+                "luckyNumber",
+                integer().not_one_of(vec![4, 13]).greater_than(0),
+            );
+        let serialized = serde_json::to_string(&schema.to_spec().unwrap()).unwrap();
+        let spec = serde_json::from_str::<SchemaSpec>(&serialized).unwrap();
+        assert_eq!(spec, schema.to_spec().unwrap());
+        let restored = from_spec(&spec).unwrap();
+        let payloads = vec![
+            json!({ "username": " FOO ", "tags": ["a", "b"], "luckyNumber": "7" }),
+            json!({ "username": "", "tags": null, "luckyNumber": 7 }),
+            json!({ "username": "foo", "rating": 6, "tags": null, "luckyNumber": 7 }),
+            json!({ "username": "foo", "tags": ["A"], "luckyNumber": 7 }),
+            json!({ "username": "foo", "tags": ["a", "b", "c"], "luckyNumber": 7 }),
+            json!({ "username": "foo", "tags": null, "luckyNumber": 13 }),
+            json!({ "username": "foo", "tags": null, "luckyNumber": 0 }),
+        ];
+        payloads.into_iter().for_each(|payload| {
+            assert_eq!(restored.validate(payload.clone()), schema.validate(payload));
+        });
+    }
+
+    #[test]
+    fn it_serializes_specs() {
+        let schema = array()
+            .label("Scores")
+            .min_length(1)
+            .of(float().min(0.0).optional().sensitive());
+        assert_eq!(
+            serde_json::to_value(schema.to_spec().unwrap()).unwrap(),
+            json!({
+                "type": "array",
+                "label": "Scores",
+                "tests": [{ "name": "min_length", "params": { "min": 1 } }],
+                "elements": {
+                    "type": "float",
+                    "optional": true,
+                    "sensitive": true,
+                    "tests": [{ "name": "min", "params": { "min": 0.0 } }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_round_trips_coercions() {
+        let schema = float().decimal_separator(',').group_separator('.');
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            json!({
+                "type": "float",
+                "coercions": { "decimal_separator": ",", "group_separator": "." }
+            })
+        );
+        let schema = from_spec(&spec).unwrap();
+        assert_eq!(schema.validate(json!("1.234,5")), Ok(json!(1234.5)));
+        let schema = string().coercions(|c| c.allow_number_to_string(false));
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            json!({ "type": "string", "coercions": { "number_to_string": false } })
+        );
+        assert!(from_spec(&spec).unwrap().validate(json!(1)).is_err());
+    }
+
+    #[test]
+    fn it_round_trips_message_overrides() {
+        let schema = string()
+            .label("Password")
+            .min_length(8)
+            .message("Password too short")
+            .max_length(64);
+        let spec = schema.to_spec().unwrap();
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            json!({
+                "type": "string",
+                "label": "Password",
+                "tests": [
+                    { "name": "min_length", "params": { "min": 8 }, "message": "Password too short" },
+                    { "name": "max_length", "params": { "max": 64 } }
+                ]
+            })
+        );
+        let restored = from_spec(&spec).unwrap();
+        for payload in &[json!("short"), json!("x".repeat(65))] {
+            assert_eq!(
+                restored.validate(payload.clone()),
+                schema.validate(payload.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn it_reports_unserializable_schemas() {
+        let registry = SchemaRegistry::new();
+        let schema = object().key("address", registry.reference("Address"));
+        assert_eq!(
+            schema.to_spec(),
+            Err(SpecError::Unserializable("reference to `Address`".into()))
+        );
+    }
+
+    #[test]
+    fn it_reports_custom_tests_as_unserializable() {
+        let schema = object().string("username", |username| {
+            username
+                .min_length(3)
+                .test("no_admin", "<label> is reserved.", |name| {
+                    Ok(name != "admin")
+                })
+        });
+        assert_eq!(
+            schema.to_spec(),
+            Err(SpecError::Unserializable("custom test `no_admin`".into()))
+        );
+        assert!(integer()
+            .test("even", "<label> must be even.", |n| Ok(n % 2 == 0))
+            .to_spec()
+            .is_err());
+    }
+
+    #[test]
+    fn it_rejects_unknown_rules() {
+        let spec = serde_json::from_value::<SchemaSpec>(json!({
+            "type": "string",
+            "tests": [{ "name": "palindrome" }]
+        }))
+        .unwrap();
+        assert_eq!(
+            from_spec(&spec).err(),
+            Some(SpecError::UnknownRule("palindrome".into()))
+        );
+        let spec = serde_json::from_value::<SchemaSpec>(json!({
+            "type": "integer",
+            "tests": [{ "name": "min", "params": { "min": "five" } }]
+        }))
+        .unwrap();
+        assert_eq!(
+            from_spec(&spec).err(),
+            Some(SpecError::InvalidParams("min.min".into()))
+        );
+    }
+}
@@ -1,32 +1,107 @@
-use super::error::{test_error, Result};
+use super::{
+    error::{field_label, test_error, Result},
+    json::Json,
+};
+
+type TestFn<T> = Box<dyn Fn(&T) -> Result<TestOutcome>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestOutcome {
+    Pass,
+    Fail,
+    FailWith(String),
+    /// Fails with the offending value, for tests that find it while checking.
+    FailMeasured(Json),
+}
+
+impl From<bool> for TestOutcome {
+    fn from(passed: bool) -> Self {
+        match passed {
+            true => TestOutcome::Pass,
+            false => TestOutcome::Fail,
+        }
+    }
+}
 
 pub struct Test<T> {
     type_: &'static str,
     message: String,
-    test: Box<Fn(&T) -> Result<bool>>,
+    is_message_overridden: bool,
+    params: Json,
+    actual: Option<fn(&T) -> Json>,
+    custom: bool,
+    test: TestFn<T>,
 }
 
 impl<T> Test<T> {
-    pub fn new<M, F>(type_: &'static str, message: M, test: F) -> Self
+    pub fn new<M, F, O>(type_: &'static str, message: M, params: Json, test: F) -> Self
     where
         M: Into<String>,
-        F: Fn(&T) -> Result<bool> + 'static,
+        F: Fn(&T) -> Result<O> + 'static,
+        O: Into<TestOutcome>,
     {
         Test {
             type_,
-            test: Box::new(test),
+            params,
+            actual: None,
+            custom: false,
+            test: Box::new(move |value| test(value).map(Into::into)),
             message: message.into(),
+            is_message_overridden: false,
+        }
+    }
+
+    pub fn type_(&self) -> &'static str {
+        self.type_
+    }
+
+    pub fn params(&self) -> &Json {
+        &self.params
+    }
+
+    pub fn message<M: Into<String>>(&mut self, message: M) {
+        self.message = message.into();
+        self.is_message_overridden = true;
+    }
+
+    pub fn message_override(&self) -> Option<&str> {
+        match self.is_message_overridden {
+            true => Some(&self.message),
+            false => None,
         }
     }
 
+    pub fn measure(&mut self, actual: fn(&T) -> Json) {
+        self.actual = Some(actual);
+    }
+
+    /// Marks the test as backed by a user closure that cannot be serialized.
+    pub fn custom(&mut self) {
+        self.custom = true;
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.custom
+    }
+
     pub fn check(&self, path: &str, label: &str, value: &T) -> Result<()> {
-        match (self.test)(value)? {
-            true => Ok(()),
-            false => Err(test_error(
-                self.type_,
-                path,
-                &self.message.replace("<label>", label),
-            )),
+        let fallback = self.message.replace("<label>", label);
+        let (error, measured) = match (self.test)(value)? {
+            TestOutcome::Pass => return Ok(()),
+            TestOutcome::Fail => (test_error(self.type_, path, &fallback), None),
+            TestOutcome::FailWith(message) => {
+                let message = message.replace("<label>", label);
+                let error = test_error(self.type_, path, &message);
+                (error.with_redacted_message(fallback), None)
+            }
+            TestOutcome::FailMeasured(actual) => {
+                (test_error(self.type_, path, &fallback), Some(actual))
+            }
+        };
+        let error = error.with_label(field_label(path, label));
+        match measured.or_else(|| self.actual.map(|actual| actual(value))) {
+            Some(actual) => Err(error.with_measure(self.params.clone(), actual)),
+            None => Err(error.with_params(self.params.clone())),
         }
     }
 }
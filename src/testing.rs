@@ -0,0 +1,101 @@
+use super::{json::Json, OkSchema};
+use std::collections::BTreeSet;
+
+#[macro_export]
+macro_rules! assert_valid {
+    ($schema:expr, $json:expr $(,)?) => {
+        $crate::testing::assert_valid(&$schema, $json)
+    };
+}
+
+#[macro_export]
+macro_rules! assert_invalid {
+    ($schema:expr, $json:expr, [$($path:expr => $code:expr),* $(,)?] $(,)?) => {
+        $crate::testing::assert_invalid(&$schema, $json, &[$(($path, $code)),*])
+    };
+}
+
+pub fn assert_valid<S: OkSchema + ?Sized>(schema: &S, json: Json) -> Json {
+    match schema.validate(json) {
+        Ok(value) => value,
+        Err(error) => panic!("expected a valid payload, but:\n{}", error.to_report()),
+    }
+}
+
+pub fn assert_invalid<S: OkSchema + ?Sized>(schema: &S, json: Json, expected: &[(&str, &str)]) {
+    let error = match schema.validate(json) {
+        Ok(value) => panic!("expected an invalid payload, but it validated as {}", value),
+        Err(error) => error,
+    };
+    let actual = error
+        .flatten()
+        .map(|error| (error.path().to_string(), error.code().to_string()))
+        .collect::<BTreeSet<_>>();
+    let expected = expected
+        .iter()
+        .map(|&(path, code)| (path.to_string(), code.to_string()))
+        .collect::<BTreeSet<_>>();
+    if actual == expected {
+        return;
+    }
+    let lines = |sign: char, findings: &mut dyn Iterator<Item = &(String, String)>| {
+        findings
+            .map(|(path, code)| format!("{} {} => {}\n", sign, path, code))
+            .collect::<String>()
+    };
+    panic!(
+        "validation findings did not match (- missing, + unexpected):\n{}{}",
+        lines('-', &mut expected.difference(&actual)),
+        lines('+', &mut actual.difference(&expected))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{object, OkSchema};
+    use serde_json::json;
+
+    fn schema() -> impl OkSchema {
+        object()
+            .string("email", |email| email.min_length(3))
+            .integer("age", |age| age.min(18))
+    }
+
+    #[test]
+    fn it_returns_the_validated_value() {
+        let value = crate::assert_valid!(schema(), json!({ "email": "a@b", "age": "21" }));
+        assert_eq!(value, json!({ "email": "a@b", "age": 21 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a valid payload, but:\n✗ 1 validation error occurred.")]
+    fn it_reports_unexpected_failures() {
+        crate::assert_valid!(schema(), json!({ "email": "a@b", "age": 1 }));
+    }
+
+    #[test]
+    fn it_matches_failed_paths_and_codes() {
+        crate::assert_invalid!(
+            schema(),
+            json!({ "email": "", "age": 1 }),
+            ["email" => "min_length", "age" => "min"]
+        );
+        crate::assert_invalid!(schema(), json!([]), ["" => "type_error"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an invalid payload")]
+    fn it_reports_unexpected_successes() {
+        crate::assert_invalid!(schema(), json!({ "email": "a@b", "age": 21 }), []);
+    }
+
+    #[test]
+    #[should_panic(expected = "(- missing, + unexpected):\n- email => matches\n+ age => min\n")]
+    fn it_reports_partial_matches() {
+        crate::assert_invalid!(
+            schema(),
+            json!({ "email": "", "age": 1 }),
+            ["email" => "min_length", "email" => "matches"]
+        );
+    }
+}
@@ -1,18 +1,32 @@
 use super::{
-    error::{type_error, Result, ValidationError, ValidationResult},
-    json::{from_json, to_json, Json, JsonType},
-    Test,
+    context::Context,
+    error::{
+        internal_error, parse_segments, segment_label, type_error, Result, ValidationError,
+        ValidationResult,
+    },
+    json::{from_json, to_json, Coercions, Json, JsonType},
+    spec::{RuleSpec, SchemaKind, SchemaSpec, SpecError},
+    Test, TestOutcome,
 };
 use serde::{de::DeserializeOwned, ser::Serialize};
 
+pub struct Transform<T> {
+    pub name: &'static str,
+    pub params: Json,
+    pub transform: Box<dyn Fn(T) -> T>,
+}
+
 pub struct Validator<T: DeserializeOwned + Serialize> {
     pub json_type: JsonType,
-    pub label: Option<&'static str>,
-    pub description: Option<&'static str>,
+    pub label: Option<String>,
+    pub description: Option<String>,
     pub is_optional: bool,
     pub is_nullable: bool,
+    pub is_sensitive: bool,
+    pub default: Option<Json>,
+    pub coercions: Coercions,
     pub tests: Vec<Test<T>>,
-    pub transforms: Vec<fn(T) -> T>,
+    pub transforms: Vec<Transform<T>>,
 }
 
 impl<T: DeserializeOwned + Serialize> Validator<T> {
@@ -23,21 +37,137 @@ impl<T: DeserializeOwned + Serialize> Validator<T> {
             description: None,
             is_optional: false,
             is_nullable: false,
+            is_sensitive: false,
+            default: None,
+            coercions: Coercions::default(),
             tests: vec![],
             transforms: vec![],
         }
     }
 
-    pub fn add_test<M, F>(&mut self, type_: &'static str, message: M, test: F)
+    pub fn add_test<M, F, O>(
+        &mut self,
+        type_: &'static str,
+        message: M,
+        params: Json,
+        test: F,
+    ) -> &mut Test<T>
     where
         M: Into<String>,
-        F: Fn(&T) -> Result<bool> + 'static,
+        F: Fn(&T) -> Result<O> + 'static,
+        O: Into<TestOutcome>,
     {
-        self.tests.push(Test::new(type_, message, test));
+        self.tests.push(Test::new(type_, message, params, test));
+        self.tests.last_mut().unwrap()
+    }
+
+    pub fn add_transform<F>(&mut self, name: &'static str, transform: F) -> &mut Transform<T>
+    where
+        F: Fn(T) -> T + 'static,
+    {
+        self.transforms.push(Transform {
+            name,
+            params: Json::Null,
+            transform: Box::new(transform),
+        });
+        self.transforms.last_mut().unwrap()
+    }
+
+    pub fn override_message(&mut self, message: &str) {
+        if let Some(test) = self.tests.last_mut() {
+            test.message(message);
+        }
+    }
+
+    pub fn to_spec(&self) -> std::result::Result<SchemaSpec, SpecError> {
+        let kind = SchemaKind::from_json_type(self.json_type).ok_or_else(|| {
+            SpecError::Unserializable(format!("schema of type `{}`", self.json_type))
+        })?;
+        Ok(SchemaSpec {
+            kind,
+            label: self.label.clone(),
+            description: self.description.clone(),
+            optional: self.is_optional,
+            nullable: self.is_nullable,
+            sensitive: self.is_sensitive,
+            default: self.default.clone(),
+            coercions: match self.coercions.is_default() {
+                true => None,
+                false => Some(self.coercions),
+            },
+            transforms: self
+                .transforms
+                .iter()
+                .map(|transform| RuleSpec {
+                    name: transform.name.into(),
+                    params: transform.params.clone(),
+                    message: None,
+                })
+                .collect(),
+            tests: self
+                .tests
+                .iter()
+                .map(|test| match test.is_custom() {
+                    true => Err(SpecError::Unserializable(format!(
+                        "custom test `{}`",
+                        test.type_()
+                    ))),
+                    false => Ok(RuleSpec {
+                        name: test.type_().into(),
+                        params: test.params().clone(),
+                        message: test.message_override().map(Into::into),
+                    }),
+                })
+                .collect::<std::result::Result<_, _>>()?,
+            elements: None,
+            properties: Default::default(),
+        })
+    }
+
+    pub fn load_spec(&mut self, spec: &SchemaSpec) {
+        self.label = spec.label.clone();
+        self.description = spec.description.clone();
+        self.is_optional = spec.optional;
+        self.is_nullable = spec.nullable;
+        self.is_sensitive = spec.sensitive;
+        self.default = spec.default.clone();
+        self.coercions = spec.coercions.unwrap_or_default();
+    }
+
+    /// Restores message overrides once the spec's tests have been rebuilt in order.
+    pub fn load_messages(&mut self, rules: &[RuleSpec]) {
+        for (test, rule) in self.tests.iter_mut().zip(rules) {
+            if let Some(message) = &rule.message {
+                test.message(message.as_str());
+            }
+        }
     }
 
-    pub fn add_transform(&mut self, transform: fn(T) -> T) {
-        self.transforms.push(transform);
+    pub fn redact(&self, error: ValidationError) -> ValidationError {
+        match self.is_sensitive {
+            true => error.redacted(),
+            false => error,
+        }
+    }
+
+    fn finish(&self, path: &str, error: ValidationError, context: &Context) -> ValidationError {
+        let depth = parse_segments(path).len();
+        let mut segments = context.segments().to_vec();
+        segments.extend(error.segments().iter().skip(depth).cloned());
+        let error = match self.label {
+            None if segments.len() == context.segments().len() => {
+                let label = segment_label(&segments);
+                error.with_label(label).with_segments(segments)
+            }
+            _ => error.with_segments(segments),
+        };
+        let error = match &self.description {
+            Some(description) if context.includes_descriptions() => {
+                error.with_description(description.as_str())
+            }
+            _ => error,
+        };
+        self.redact(error)
     }
 
     pub fn exec(
@@ -45,30 +175,79 @@ impl<T: DeserializeOwned + Serialize> Validator<T> {
         path: &str,
         value: Option<Json>,
         all_errors: &mut Vec<ValidationError>,
+        context: &mut Context,
     ) -> ValidationResult<Option<Json>> {
-        let label = self.label.unwrap_or(path);
+        context.enter(path, self.json_type);
+        let label = self.label.as_deref().unwrap_or(path);
+        let received = JsonType::from(&value);
+        let original = match context.reports_coercions() {
+            true => value.clone(),
+            false => None,
+        };
+        let value = match value {
+            None if self.is_optional => match &self.default {
+                Some(default) => Some(default.clone()),
+                None => return Ok(None),
+            },
+            value => value,
+        };
         let coersion = match value {
-            None if self.is_optional => return Ok(None),
-            None => Err(type_error(path, label, self.json_type)),
+            None => Err(type_error(path, label, self.json_type, None)),
             Some(Json::Null) if self.is_nullable => return Ok(value),
-            Some(json) => self.json_type.coerce(path, label, json),
+            Some(json) => self.json_type.coerce(path, label, json, &self.coercions),
         };
-        if coersion.is_err() {
-            return Err(all_errors.push(coersion.unwrap_err()));
+        let json = match coersion {
+            Ok(json) => json,
+            Err(error) => {
+                all_errors.push(self.finish(path, error, context));
+                return Err(());
+            }
+        };
+        if received != JsonType::from(&json) {
+            context.coercion(path, received, self.json_type);
         }
-        let json = coersion.unwrap();
-        let t = self
-            .transforms
-            .iter()
-            .fold(from_json(json).unwrap(), |t, transform| transform(t));
+        if let Some(original) = original {
+            context.report(path, "coercion", original, json.clone(), self.is_sensitive);
+        }
+        let t = match from_json::<T>(json) {
+            Ok(t) => t,
+            Err(err) => {
+                all_errors.push(self.finish(path, internal_error(path, err), context));
+                return Err(());
+            }
+        };
+        let t = self.transforms.iter().fold(t, |t, transform| {
+            if !context.reports_coercions() {
+                return (transform.transform)(t);
+            }
+            let from = to_json(&t).unwrap_or(Json::Null);
+            let t = (transform.transform)(t);
+            let to = to_json(&t).unwrap_or(Json::Null);
+            context.report(path, transform.name, from, to, self.is_sensitive);
+            t
+        });
         let mut errors = self
             .tests
             .iter()
-            .filter_map(|test| test.check(path, label, &t).err())
+            .filter_map(|test| {
+                let checked = test.check(path, label, &t);
+                context.test(path, test.type_(), checked.is_ok());
+                checked.err().map(|error| self.finish(path, error, context))
+            })
             .collect::<Vec<ValidationError>>();
-        if errors.is_empty() {
-            return Ok(Some(to_json(t).unwrap()));
+        if !errors.is_empty() {
+            all_errors.append(&mut errors);
+            return Err(());
+        }
+        match to_json(t) {
+            Ok(Json::Null) => Err(internal_error(
+                path,
+                "the value cannot be represented as JSON",
+            )),
+            Ok(json) => Ok(json),
+            Err(err) => Err(internal_error(path, err)),
         }
-        Err(all_errors.append(&mut errors))
+        .map(Some)
+        .map_err(|error| all_errors.push(self.finish(path, error, context)))
     }
 }